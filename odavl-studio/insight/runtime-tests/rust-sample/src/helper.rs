@@ -1,3 +1,12 @@
+use std::process::exit;
+
+// BAD: unreachable code after a bare `exit(..)` call, imported via `use`
+// rather than spelled out as `std::process::exit`.
+pub fn bail_out() {
+    exit(1);
+    println!("This will never execute either");
+}
+
 pub fn process_data(data: Vec<i32>) {
     let _unused_result = 100; // BAD: Unused variable
     
@@ -11,3 +20,43 @@ pub fn process_data(data: Vec<i32>) {
     panic!("Another intentional panic!");
     println!("This will never execute");
 }
+
+// BAD: infinite loop with no break, so the trailing println! never runs
+pub fn spin_forever() {
+    loop {
+        println!("spinning");
+    }
+    println!("This will never execute either");
+}
+
+// BAD: dividing by the literal -1 panics on overflow when `x` is the
+// type's minimum value, since `-x` doesn't fit back into the type.
+pub fn divide_by_minus_one(x: i32) -> i32 {
+    x / -1
+}
+
+// BAD: the shift amount is a runtime value, so an unbounded `n` panics
+// with "attempt to shift left with overflow" -- the constant `1u32` is
+// on the *left*, and says nothing about whether the shift amount is in
+// range.
+pub fn shift_by_runtime_amount(n: u32) -> u32 {
+    1u32 << n
+}
+
+// BAD: `0 - i32::MIN` overflows and panics in debug -- the constant `0`
+// is on the *left*, and says nothing about whether the runtime operand
+// overflows.
+pub fn negate_via_subtraction(x: i32) -> i32 {
+    0 - x
+}
+
+// BAD: `x`'s real declared width is 8 bits, so shifting by the constant
+// 10 always panics with "attempt to shift left with overflow" -- a front
+// end that assumed every operand is `i32` would miss this entirely, since
+// 10 is nowhere near a 32-bit shift's overflow threshold. `rustc` proves
+// the same thing at compile time (hence the `allow`); the lint's job is
+// to catch this in code where the shift amount isn't a bare literal.
+#[allow(arithmetic_overflow)]
+pub fn shift_a_u8_by_a_too_wide_amount(x: u8) -> u8 {
+    x << 10
+}
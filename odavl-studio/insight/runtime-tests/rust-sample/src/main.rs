@@ -1,3 +1,10 @@
+// This fixture exists to be analyzed, not to be clean: the lints below
+// are exactly the anti-patterns `insight`'s passes are supposed to flag,
+// so they're silenced here rather than rewritten away.
+#![allow(unreachable_code, clippy::needless_range_loop)]
+
+use std::sync::Mutex;
+
 mod helper;
 
 fn main() {
@@ -14,5 +21,65 @@ fn main() {
         panic!("Intentional panic for testing!");
     }
     
+    let value = *value;
+    helper::divide_by_minus_one(value);
     helper::process_data(data);
+    helper::spin_forever();
+    helper::bail_out();
+    helper::shift_by_runtime_amount(value as u32);
+    helper::negate_via_subtraction(value);
+    helper::shift_a_u8_by_a_too_wide_amount(value as u8);
+    unreachable_guard_does_not_hide_live_code();
+    let shared = Mutex::new(0);
+    guard_held_across_a_panicking_call(&shared);
+    let other_shared = Mutex::new(0);
+    guard_locked_on_one_branch_is_held_at_the_merge(&other_shared, false);
+    hazard_left_open_across_a_panicking_call();
+}
+
+// BAD: holds the `Mutex` guard across the call into `helper::process_data`,
+// which panics -- an unwind here poisons the lock for every other thread
+// that later tries to lock `shared`.
+fn guard_held_across_a_panicking_call(shared: &Mutex<i32>) {
+    let guard = shared.lock().unwrap();
+    helper::process_data(vec![1, 2, 3]);
+    drop(guard);
+}
+
+// BAD: the guard is only dropped on one branch, so it's still (possibly)
+// held at the merge point where both branches flow into the call into
+// `helper::process_data`, which panics -- a lint that didn't track
+// liveness across the branch/merge would either miss this (if it saw only
+// the dropping branch) or flag it unconditionally (if it ignored branches
+// entirely and assumed the guard lives to the end of the function).
+fn guard_locked_on_one_branch_is_held_at_the_merge(shared: &Mutex<i32>, drop_early: bool) {
+    let guard = shared.lock().unwrap();
+    if drop_early {
+        drop(guard);
+    } else {
+        // `guard` stays locked down this path.
+    }
+    helper::process_data(vec![4, 5, 6]);
+}
+
+// BAD: `state` is swapped out for an empty placeholder, then
+// `helper::process_data` (which panics) runs before the real value is
+// written back -- on unwind, nothing ever restores `state` to something
+// meaningful.
+#[allow(unused_assignments)]
+fn hazard_left_open_across_a_panicking_call() {
+    let mut state = vec![1, 2, 3];
+    let old = std::mem::replace(&mut state, vec![-1]);
+    helper::process_data(old);
+    state = vec![4, 5, 6];
+}
+
+// GOOD (for the lint's sake): a statically-false guard's then-branch is
+// dead, but everything after the `if` is still reachable and must not be
+// flagged.
+fn unreachable_guard_does_not_hide_live_code() -> i32 {
+    if false {
+        panic!("never happens");
+    }
+    42
 }
@@ -0,0 +1,91 @@
+//! Shared control-flow-graph scaffolding for the CFG-based passes
+//! ([`crate::exception_safety`], [`crate::unreachable_code`],
+//! [`crate::lock_poisoning`]).
+//!
+//! Generic over the per-block statement type so each pass keeps its own
+//! statement shape (divergence info, hazard effects, ...) without each
+//! reimplementing block/edge bookkeeping and constant-condition handling.
+
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct BlockId(pub u32);
+
+#[derive(Debug, Clone)]
+pub struct Block<S> {
+    pub stmts: Vec<S>,
+    pub successors: Vec<BlockId>,
+}
+
+impl<S> Default for Block<S> {
+    fn default() -> Self {
+        Self { stmts: Vec::new(), successors: Vec::new() }
+    }
+}
+
+/// A function's control-flow graph, built so that branching on a literal
+/// `true`/`false` condition contributes only the edge that's actually
+/// reachable — e.g. `if true { panic!() }` contributes just the
+/// then-branch edge, so there's no else-branch edge to keep whatever
+/// follows artificially reachable.
+#[derive(Debug, Clone)]
+pub struct Cfg<S> {
+    pub entry: BlockId,
+    pub blocks: HashMap<BlockId, Block<S>>,
+}
+
+impl<S> Default for Cfg<S> {
+    fn default() -> Self {
+        Self { entry: BlockId::default(), blocks: HashMap::new() }
+    }
+}
+
+impl<S> Cfg<S> {
+    pub fn new(entry: BlockId) -> Self {
+        Self { entry, blocks: HashMap::new() }
+    }
+
+    pub fn add_block(&mut self, id: BlockId, block: Block<S>) {
+        self.blocks.insert(id, block);
+    }
+
+    /// Adds the edge(s) leaving an `if`. When `condition_is_const` is
+    /// `Some(value)`, only the edge matching `value` is added, modeling a
+    /// constant condition making the other branch statically
+    /// unreachable rather than merely untaken at runtime.
+    pub fn add_if_edges(
+        &mut self,
+        from: BlockId,
+        condition_is_const: Option<bool>,
+        then_block: BlockId,
+        else_block: Option<BlockId>,
+    ) {
+        let successors = &mut self.blocks.entry(from).or_default().successors;
+        match condition_is_const {
+            Some(true) => successors.push(then_block),
+            Some(false) => {
+                if let Some(else_block) = else_block {
+                    successors.push(else_block);
+                }
+            }
+            None => {
+                successors.push(then_block);
+                if let Some(else_block) = else_block {
+                    successors.push(else_block);
+                }
+            }
+        }
+    }
+
+    /// Maps each block to its predecessors, the reverse of the recorded
+    /// successor edges.
+    pub fn predecessors(&self) -> HashMap<BlockId, Vec<BlockId>> {
+        let mut preds: HashMap<BlockId, Vec<BlockId>> = HashMap::new();
+        for (&id, block) in &self.blocks {
+            for &succ in &block.successors {
+                preds.entry(succ).or_default().push(id);
+            }
+        }
+        preds
+    }
+}
@@ -0,0 +1,355 @@
+//! Checked-arithmetic lint.
+//!
+//! Flags `+`, `-`, `*`, and `<<` on integer types where the operands are
+//! not provably in range for the declared width, since these panic in
+//! debug builds and silently wrap in release. Each diagnostic offers a
+//! structured autofix choice between `checked_*` (returns `Option`),
+//! `saturating_*`, and `wrapping_*` (for `<<`, which has no
+//! `saturating_shl`, `overflowing_shl` takes the place of `saturating_*`),
+//! so the user picks the semantics rather than us guessing.
+
+use crate::diagnostics::{Diagnostic, Severity, Suggestion};
+use crate::model::{FunctionId, Span};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArithOp {
+    Add,
+    Sub,
+    Mul,
+    Shl,
+}
+
+impl ArithOp {
+    fn checked_method(self) -> &'static str {
+        match self {
+            ArithOp::Add => "checked_add",
+            ArithOp::Sub => "checked_sub",
+            ArithOp::Mul => "checked_mul",
+            ArithOp::Shl => "checked_shl",
+        }
+    }
+
+    /// The second suggested alternative for every op but `Shl`: `std` has
+    /// no `saturating_shl` (only `checked_shl`/`wrapping_shl`/
+    /// `overflowing_shl`), so `lint` special-cases `Shl` to offer
+    /// `overflowing_shl` instead of calling this.
+    fn saturating_method(self) -> &'static str {
+        match self {
+            ArithOp::Add => "saturating_add",
+            ArithOp::Sub => "saturating_sub",
+            ArithOp::Mul => "saturating_mul",
+            ArithOp::Shl => unreachable!("Shl has no saturating_shl; lint() special-cases it"),
+        }
+    }
+
+    fn wrapping_method(self) -> &'static str {
+        match self {
+            ArithOp::Add => "wrapping_add",
+            ArithOp::Sub => "wrapping_sub",
+            ArithOp::Mul => "wrapping_mul",
+            ArithOp::Shl => "wrapping_shl",
+        }
+    }
+}
+
+/// An unchecked arithmetic expression found in a function body, e.g. the
+/// `data[i] * 1000000` in `process_data`.
+#[derive(Debug, Clone)]
+pub struct ArithExpr {
+    pub function: FunctionId,
+    pub span: Span,
+    pub op: ArithOp,
+    pub lhs: String,
+    /// Whether `lhs` needs wrapping in parens before a method call is
+    /// appended to it in an autofix suggestion, e.g. `true` for the `a -
+    /// b` in `a - b - c` (a compound expression), `false` for an atom
+    /// like `data[i]`.
+    pub lhs_needs_parens: bool,
+    pub rhs: String,
+    /// A statically known constant operand (e.g. `1000000`): a literal on
+    /// either side for `Add`/`Mul`, but only a literal right operand for
+    /// `Sub`/`Shl`, since `provably_safe` treats it as the amount
+    /// subtracted or shifted. `None` otherwise, and overflow then can't be
+    /// ruled out.
+    pub const_operand: Option<i128>,
+    /// Bit width of the operand type, e.g. 32 for `i32`/`u32`.
+    pub bit_width: u32,
+    pub is_signed: bool,
+}
+
+/// Whether overflow is provably impossible for `expr` given its declared
+/// width. Only constant operands are checked; when both operands are
+/// runtime values we can't prove anything and must not suppress the
+/// warning.
+fn provably_safe(expr: &ArithExpr) -> bool {
+    let Some(k) = expr.const_operand else {
+        return false;
+    };
+
+    // A shift only panics (in debug) when the shift amount reaches or
+    // exceeds the operand's bit width; the value being shifted has no
+    // bearing on it, and there's no "wraps in release" story either, since
+    // both profiles just silently discard the high bits either way.
+    if expr.op == ArithOp::Shl {
+        return k >= 0 && (k as u32) < expr.bit_width;
+    }
+
+    // u128's range doesn't fit in `i128`, so the `min`/`max` bound math
+    // below (which represents bounds as `i128`) can't express it without
+    // overflowing the shift that builds `max`. Do this one case in u128.
+    if !expr.is_signed && expr.bit_width >= 128 {
+        let Ok(k) = u128::try_from(k) else { return false };
+        return match expr.op {
+            ArithOp::Add => u128::MAX.checked_add(k).is_some(),
+            ArithOp::Sub => 0u128.checked_sub(k).is_some(),
+            ArithOp::Mul => [u128::MAX, 0].into_iter().all(|x| x.checked_mul(k).is_some()),
+            ArithOp::Shl => unreachable!("handled above"),
+        };
+    }
+
+    // `i128`'s own bounds can't be derived via `1i128 << (bit_width - 1)`:
+    // that shift evaluates to `i128::MIN`, and negating it to get `-half`
+    // overflows `i128` itself. Use the type's bounds directly instead.
+    if expr.is_signed && expr.bit_width >= 128 {
+        let (min, max) = (i128::MIN, i128::MAX);
+        return match expr.op {
+            ArithOp::Add => max.checked_add(k).is_some_and(|v| v <= max) && min.checked_add(k).is_some_and(|v| v >= min),
+            ArithOp::Sub => max.checked_sub(k).is_some_and(|v| v <= max) && min.checked_sub(k).is_some_and(|v| v >= min),
+            ArithOp::Mul => [max, min].into_iter().all(|x| {
+                x.checked_mul(k).is_some_and(|v| v >= min && v <= max)
+            }),
+            ArithOp::Shl => unreachable!("handled above"),
+        };
+    }
+
+    let (min, max): (i128, i128) = if expr.is_signed {
+        let half = 1i128 << (expr.bit_width - 1);
+        (-half, half - 1)
+    } else {
+        (0, (1i128 << expr.bit_width) - 1)
+    };
+
+    match expr.op {
+        // Worst case for a variable `x` in range is `max`; if `max op k` is
+        // still in range, the expression can't overflow regardless of `x`.
+        ArithOp::Add => max.checked_add(k).is_some_and(|v| v <= max) && min.checked_add(k).is_some_and(|v| v >= min),
+        ArithOp::Sub => max.checked_sub(k).is_some_and(|v| v <= max) && min.checked_sub(k).is_some_and(|v| v >= min),
+        // The extremal products aren't always `max * k` and `min * k` in
+        // that pairing — for negative `k`, `x = max` yields the most
+        // negative result and `x = min` the most positive. Check both
+        // operands against both bounds rather than assuming the sign.
+        ArithOp::Mul => [max, min].into_iter().all(|x| {
+            x.checked_mul(k).is_some_and(|v| v >= min && v <= max)
+        }),
+        ArithOp::Shl => unreachable!("handled above"),
+    }
+}
+
+/// Lints a set of arithmetic expressions, suppressing the ones that
+/// provably cannot overflow and attaching a structured autofix choice to
+/// the rest.
+pub fn lint(exprs: &[ArithExpr]) -> Vec<Diagnostic> {
+    exprs
+        .iter()
+        .filter(|expr| !provably_safe(expr))
+        .map(|expr| {
+            let op = expr.op;
+            let message = if op == ArithOp::Shl {
+                "this shift amount is not provably less than the operand's bit width (panics in debug)"
+            } else {
+                "this arithmetic is not provably free of overflow (panics in debug, wraps in release)"
+            };
+            // A method call binds tighter than the binary operator it's
+            // replacing, so a compound `lhs` (e.g. `a - b`) needs parens
+            // before `.checked_sub(c)` is appended, or the suggestion
+            // parses as `a - (b.checked_sub(c))` instead of `(a -
+            // b).checked_sub(c)`.
+            let lhs =
+                if expr.lhs_needs_parens { format!("({})", expr.lhs) } else { expr.lhs.clone() };
+            let clamp_suggestion = if op == ArithOp::Shl {
+                Suggestion {
+                    message: "get the wrapped result plus an overflow flag via `overflowing_shl`".to_string(),
+                    replacement: format!("{lhs}.overflowing_shl({})", expr.rhs),
+                }
+            } else {
+                Suggestion {
+                    message: "clamp to the type's bounds via `saturating_*`".to_string(),
+                    replacement: format!("{lhs}.{}({})", op.saturating_method(), expr.rhs),
+                }
+            };
+            Diagnostic::new(expr.function.clone(), expr.span, Severity::Warning, message)
+            .with_suggestion(Suggestion {
+                message: "propagate overflow as `None` via `checked_*`".to_string(),
+                replacement: format!("{lhs}.{}({})", op.checked_method(), expr.rhs),
+            })
+            .with_suggestion(clamp_suggestion)
+            .with_suggestion(Suggestion {
+                message: "wrap around via `wrapping_*`".to_string(),
+                replacement: format!("{lhs}.{}({})", op.wrapping_method(), expr.rhs),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Mirrors `data[i] * 1000000` from the runtime-tests fixture's
+    /// `process_data`: an `i32` multiplied by a constant large enough
+    /// that `i32::MAX * 1_000_000` genuinely overflows, so the lint must
+    /// not suppress it.
+    fn fixture_expr() -> ArithExpr {
+        ArithExpr {
+            function: FunctionId::new("helper::process_data"),
+            span: Span { line: 6, column: 22 },
+            op: ArithOp::Mul,
+            lhs: "data[i]".to_string(),
+            lhs_needs_parens: false,
+            rhs: "1000000".to_string(),
+            const_operand: Some(1_000_000),
+            bit_width: 32,
+            is_signed: true,
+        }
+    }
+
+    #[test]
+    fn flags_the_fixture_multiplication_with_all_three_autofixes() {
+        let diagnostics = lint(&[fixture_expr()]);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].suggestions.len(), 3);
+    }
+
+    /// Regression test: the autofix replacements used to hard-code the
+    /// literal word `rhs` instead of the expression's actual right-hand
+    /// operand, producing invalid Rust like `data[i].checked_mul(rhs)`.
+    #[test]
+    fn autofix_replacements_use_the_real_right_hand_operand() {
+        let diagnostics = lint(&[fixture_expr()]);
+        let replacements: Vec<&str> =
+            diagnostics[0].suggestions.iter().map(|s| s.replacement.as_str()).collect();
+        assert_eq!(
+            replacements,
+            vec!["data[i].checked_mul(1000000)", "data[i].saturating_mul(1000000)", "data[i].wrapping_mul(1000000)"]
+        );
+    }
+
+    #[test]
+    fn negative_multiplier_is_not_wrongly_proven_safe() {
+        let expr = ArithExpr { const_operand: Some(-1_000_000), ..fixture_expr() };
+        assert!(!provably_safe(&expr));
+
+        let i8_expr =
+            ArithExpr { bit_width: 8, const_operand: Some(-128), ..fixture_expr() };
+        assert!(!provably_safe(&i8_expr));
+    }
+
+    #[test]
+    fn multiply_by_zero_or_one_is_provably_safe() {
+        assert!(provably_safe(&ArithExpr { const_operand: Some(0), ..fixture_expr() }));
+        assert!(provably_safe(&ArithExpr { const_operand: Some(1), ..fixture_expr() }));
+    }
+
+    /// `x << 3` on an `i32` can never panic — a shift's safety depends only
+    /// on the shift amount versus the bit width, never on the value being
+    /// shifted — so this must be proven safe regardless of how large `x`
+    /// can get.
+    #[test]
+    fn small_shift_amount_is_provably_safe_regardless_of_the_shifted_value() {
+        let expr = ArithExpr { op: ArithOp::Shl, const_operand: Some(3), ..fixture_expr() };
+        assert!(provably_safe(&expr));
+        assert!(lint(&[expr]).is_empty());
+    }
+
+    #[test]
+    fn shift_amount_at_or_past_the_bit_width_is_not_provably_safe() {
+        let at_width = ArithExpr { op: ArithOp::Shl, const_operand: Some(32), ..fixture_expr() };
+        assert!(!provably_safe(&at_width));
+
+        let negative = ArithExpr { op: ArithOp::Shl, const_operand: Some(-1), ..fixture_expr() };
+        assert!(!provably_safe(&negative));
+    }
+
+    /// Regression test: `std` has no `saturating_shl`, so the autofix for
+    /// a not-provably-safe shift used to suggest `x.saturating_shl(n)`,
+    /// which doesn't compile. The second suggestion must be
+    /// `overflowing_shl` instead, and the other two still `checked_shl`/
+    /// `wrapping_shl`.
+    #[test]
+    fn shift_autofix_suggests_overflowing_shl_not_saturating_shl() {
+        let expr = ArithExpr { op: ArithOp::Shl, lhs: "1u32".to_string(), rhs: "n".to_string(), const_operand: None, ..fixture_expr() };
+        let diagnostics = lint(&[expr]);
+        let replacements: Vec<&str> =
+            diagnostics[0].suggestions.iter().map(|s| s.replacement.as_str()).collect();
+        assert_eq!(replacements, vec!["1u32.checked_shl(n)", "1u32.overflowing_shl(n)", "1u32.wrapping_shl(n)"]);
+    }
+
+    /// Regression test: a compound `lhs` (anything that isn't a single
+    /// atom) used to be spliced into the autofix replacement unparenthesized,
+    /// producing `a-b.checked_sub(c)` for `a - b - c` — which parses as
+    /// `a - (b.checked_sub(c))`, not `(a - b).checked_sub(c)`, and doesn't
+    /// even type-check.
+    #[test]
+    fn autofix_parenthesizes_a_compound_left_hand_side() {
+        let expr = ArithExpr {
+            op: ArithOp::Sub,
+            lhs: "a-b".to_string(),
+            lhs_needs_parens: true,
+            rhs: "c".to_string(),
+            const_operand: None,
+            ..fixture_expr()
+        };
+        let diagnostics = lint(&[expr]);
+        let replacements: Vec<&str> =
+            diagnostics[0].suggestions.iter().map(|s| s.replacement.as_str()).collect();
+        assert_eq!(
+            replacements,
+            vec!["(a-b).checked_sub(c)", "(a-b).saturating_sub(c)", "(a-b).wrapping_sub(c)"]
+        );
+    }
+
+    /// Regression test: analyzing a `u128` operand used to panic the
+    /// analyzer itself (`1i128 << 128` overflows) instead of returning a
+    /// lint result.
+    #[test]
+    fn u128_operand_does_not_panic_the_analyzer() {
+        let safe = ArithExpr {
+            op: ArithOp::Add,
+            bit_width: 128,
+            is_signed: false,
+            const_operand: Some(0),
+            ..fixture_expr()
+        };
+        assert!(provably_safe(&safe));
+
+        let unsafe_add = ArithExpr {
+            op: ArithOp::Add,
+            bit_width: 128,
+            is_signed: false,
+            const_operand: Some(1),
+            ..fixture_expr()
+        };
+        assert!(!provably_safe(&unsafe_add));
+    }
+
+    #[test]
+    fn i128_operand_does_not_panic_the_analyzer() {
+        let safe = ArithExpr {
+            op: ArithOp::Add,
+            bit_width: 128,
+            is_signed: true,
+            const_operand: Some(0),
+            ..fixture_expr()
+        };
+        assert!(provably_safe(&safe));
+
+        let unsafe_add = ArithExpr {
+            op: ArithOp::Add,
+            bit_width: 128,
+            is_signed: true,
+            const_operand: Some(1),
+            ..fixture_expr()
+        };
+        assert!(!provably_safe(&unsafe_add));
+    }
+}
@@ -0,0 +1,42 @@
+//! Diagnostic reporting shared by all analysis passes.
+
+use crate::model::{FunctionId, Span};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// A proposed fix the user can apply, rendered as a textual replacement.
+#[derive(Debug, Clone)]
+pub struct Suggestion {
+    pub message: String,
+    pub replacement: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub function: FunctionId,
+    pub span: Span,
+    pub severity: Severity,
+    pub message: String,
+    pub suggestions: Vec<Suggestion>,
+}
+
+impl Diagnostic {
+    pub fn new(function: FunctionId, span: Span, severity: Severity, message: impl Into<String>) -> Self {
+        Self {
+            function,
+            span,
+            severity,
+            message: message.into(),
+            suggestions: Vec::new(),
+        }
+    }
+
+    pub fn with_suggestion(mut self, suggestion: Suggestion) -> Self {
+        self.suggestions.push(suggestion);
+        self
+    }
+}
@@ -0,0 +1,280 @@
+//! Exception-safety pass.
+//!
+//! Finds regions of code that put an invariant into a temporarily broken
+//! state and then run panic-capable code before restoring it: on unwind,
+//! the caller (or another thread, for shared state) observes the broken
+//! invariant. This matters most inside `unsafe` blocks, where a mid-unwind
+//! observation can be a memory-safety violation rather than just a logic
+//! bug.
+//!
+//! The core is a dataflow analysis over the CFG with a lattice of "live
+//! hazards": the set of not-yet-restored locations reaching a given
+//! program point. `IN[block]` is the join (set union) of every
+//! predecessor's `OUT`, computed to a fixed point over the block graph —
+//! not just a span-ordered walk — so a hazard opened on one branch is
+//! correctly seen as live on a sibling branch that merges back in before
+//! a panic point, and correctly *not* seen as live on a branch that never
+//! executed it.
+//!
+//! [`crate::source`] lowers a recognized subset of real hazard shapes —
+//! `mem::replace(&mut place, ..)`/`ptr::write(place, ..)` as the open, a
+//! plain `place = ..;` as the close — into this pass's `Cfg`; see its
+//! module doc comment for exactly which shapes it recognizes. The tests
+//! below still use hand-built `Cfg`s directly, to exercise the dataflow
+//! itself (branch merges, the abort-strategy escalation, diagnostic
+//! ordering) independent of what the front end currently lowers.
+
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+use crate::diagnostics::{Diagnostic, Severity, Suggestion};
+use crate::model::{FunctionId, Span};
+use crate::panic_analysis::PanicFacts;
+use crate::panic_strategy::{AnalysisContext, PanicStrategy};
+
+pub use crate::cfg::BlockId;
+
+/// One effect a block has on hazard liveness, in program order. A hazard
+/// is an operation that moves a value out of, or into, a half-initialized
+/// state: `mem::replace`, `ptr::write`, a partial move via destructuring,
+/// and similar.
+#[derive(Debug, Clone)]
+pub enum Effect {
+    OpenHazard(String),
+    CloseHazard(String),
+    PanicPoint(Span),
+}
+
+pub type Block = crate::cfg::Block<Effect>;
+pub type Cfg = crate::cfg::Cfg<Effect>;
+
+/// The lattice value at a program point: which hazard locations are
+/// currently live (opened but not yet restored). Join is set union.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LiveHazards(BTreeSet<String>);
+
+impl LiveHazards {
+    pub fn join(&mut self, other: &LiveHazards) {
+        self.0.extend(other.0.iter().cloned());
+    }
+}
+
+/// Applies one effect's hazard-liveness update in place. Shared by the
+/// fixed-point transfer function and the final diagnosing walk, so the
+/// two can't silently drift apart on what opens or closes a hazard.
+fn apply_effect(live: &mut BTreeSet<String>, effect: &Effect) {
+    match effect {
+        Effect::OpenHazard(location) => {
+            live.insert(location.clone());
+        }
+        Effect::CloseHazard(location) => {
+            live.remove(location);
+        }
+        Effect::PanicPoint(_) => {}
+    }
+}
+
+/// Applies a block's effects to an incoming `LiveHazards`.
+fn transfer(block: &Block, input: &LiveHazards) -> LiveHazards {
+    let mut live = input.clone();
+    for effect in &block.stmts {
+        apply_effect(&mut live.0, effect);
+    }
+    live
+}
+
+/// Runs the hazard-liveness dataflow to a fixed point, then walks each
+/// block once more from its fixed-point `IN` to report every panic point
+/// reached with a non-empty live set.
+///
+/// Nothing is reported unless `panic_facts.may_panic(function)` holds.
+/// Past that gate, every `PanicPoint` in `cfg` counts as panic-capable: a
+/// panic point carries no callee, so it is not checked against
+/// `panic_facts` on its own. [`crate::source`] turns every top-level call
+/// statement into a `PanicPoint`, whether or not its callee can panic.
+///
+/// Under `panic = "abort"` (per `ctx`), the process terminates at the
+/// panic point rather than unwinding back to a caller, so there's no
+/// observer left to see the broken invariant; what we instead flag is
+/// that any `Drop`-based restore the user might reach for will never run.
+///
+/// Diagnostics are returned sorted by source position: block iteration
+/// order isn't deterministic (`Cfg::blocks` is a `HashMap`), and callers
+/// (snapshot tests, CI reports) need stable, reproducible output.
+pub fn check(function: &FunctionId, cfg: &Cfg, panic_facts: &PanicFacts, ctx: &AnalysisContext) -> Vec<Diagnostic> {
+    if !panic_facts.may_panic(function) {
+        return Vec::new();
+    }
+
+    let mut inputs: HashMap<BlockId, LiveHazards> =
+        cfg.blocks.keys().map(|&id| (id, LiveHazards::default())).collect();
+    let mut worklist: VecDeque<BlockId> = cfg.blocks.keys().copied().collect();
+
+    while let Some(id) = worklist.pop_front() {
+        let Some(block) = cfg.blocks.get(&id) else { continue };
+        let out = transfer(block, &inputs[&id]);
+
+        for &succ in &block.successors {
+            let Some(succ_in) = inputs.get(&succ).cloned() else { continue };
+            let mut joined = succ_in.clone();
+            joined.join(&out);
+            if joined != succ_in {
+                inputs.insert(succ, joined);
+                worklist.push_back(succ);
+            }
+        }
+    }
+
+    let mut diagnostics = Vec::new();
+    for (id, block) in &cfg.blocks {
+        let mut live = inputs[id].clone();
+        for effect in &block.stmts {
+            if let Effect::PanicPoint(span) = effect {
+                if !live.0.is_empty() {
+                    let locations = live.0.iter().cloned().collect::<Vec<_>>().join(", ");
+                    diagnostics.push(match ctx.panic_strategy {
+                        PanicStrategy::Unwind => Diagnostic::new(
+                            function.clone(),
+                            *span,
+                            Severity::Error,
+                            format!("panic-capable code runs while {locations} is in a half-restored state"),
+                        )
+                        .with_suggestion(Suggestion {
+                            message: "wrap the hazard in a scope guard (RAII) that restores it on drop, including on unwind"
+                                .to_string(),
+                            replacement: "let _guard = scopeguard::guard((), |_| { /* restore invariant */ });".to_string(),
+                        }),
+                        PanicStrategy::Abort => Diagnostic::new(
+                            function.clone(),
+                            *span,
+                            Severity::Warning,
+                            format!(
+                                "panic-capable code runs while {locations} is unrestored; under `panic = \"abort\"` \
+                                 the process terminates here, so any `Drop`-based cleanup you rely on will not run"
+                            ),
+                        ),
+                    });
+                }
+            }
+            apply_effect(&mut live.0, effect);
+        }
+    }
+
+    diagnostics.sort_by_key(|d| (d.span.line, d.span.column));
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::panic_analysis::{self, LocalPanicFacts, PanicOrigin};
+
+    fn panicking_function_facts(function: &FunctionId) -> PanicFacts {
+        let mut graph = crate::model::CallGraph::new();
+        graph.add_function(function.clone());
+        let mut local = HashMap::new();
+        local.insert(
+            function.clone(),
+            LocalPanicFacts { origins: vec![(Span { line: 11, column: 5 }, PanicOrigin::ExplicitPanic)] },
+        );
+        panic_analysis::propagate(&graph, &local)
+    }
+
+    /// A hazard opened only on one branch of an `if`, with both branches
+    /// merging back into a block that panics. A span-ordered walk with no
+    /// notion of branches would have missed this (or flagged it
+    /// unconditionally regardless of which branch ran); the real
+    /// fixed-point join must mark the hazard live at the merge because
+    /// *some* predecessor path left it open.
+    #[test]
+    fn hazard_opened_on_one_branch_is_live_at_the_merge() {
+        let function = FunctionId::new("helper::process_data");
+        let panic_facts = panicking_function_facts(&function);
+        let ctx = AnalysisContext::default();
+
+        let entry = BlockId(0);
+        let opens_hazard = BlockId(1);
+        let does_not_open = BlockId(2);
+        let merge = BlockId(3);
+
+        let mut cfg = Cfg::new(entry);
+        cfg.add_block(entry, Block { stmts: vec![], successors: vec![opens_hazard, does_not_open] });
+        cfg.add_block(
+            opens_hazard,
+            Block { stmts: vec![Effect::OpenHazard("state".to_string())], successors: vec![merge] },
+        );
+        cfg.add_block(does_not_open, Block { stmts: vec![], successors: vec![merge] });
+        cfg.add_block(
+            merge,
+            Block { stmts: vec![Effect::PanicPoint(Span { line: 20, column: 1 })], successors: vec![] },
+        );
+
+        let diagnostics = check(&function, &cfg, &panic_facts, &ctx);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("state"));
+    }
+
+    /// Under `panic = "abort"` the pass still fires, but describes the
+    /// dead `Drop` cleanup instead of an observed half-restored state.
+    #[test]
+    fn abort_strategy_escalates_to_a_dead_cleanup_warning() {
+        let function = FunctionId::new("helper::process_data");
+        let panic_facts = panicking_function_facts(&function);
+        let ctx = AnalysisContext { panic_strategy: PanicStrategy::Abort };
+
+        let entry = BlockId(0);
+        let mut cfg = Cfg::new(entry);
+        cfg.add_block(
+            entry,
+            Block {
+                stmts: vec![
+                    Effect::OpenHazard("state".to_string()),
+                    Effect::PanicPoint(Span { line: 5, column: 1 }),
+                ],
+                successors: vec![],
+            },
+        );
+
+        let diagnostics = check(&function, &cfg, &panic_facts, &ctx);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("will not run"));
+    }
+
+    /// Two panic points, each reached with a hazard live, in blocks whose
+    /// `HashMap` iteration order isn't guaranteed: output must still come
+    /// back sorted by source position.
+    #[test]
+    fn diagnostics_are_sorted_by_span_regardless_of_block_iteration_order() {
+        let function = FunctionId::new("helper::process_data");
+        let panic_facts = panicking_function_facts(&function);
+        let ctx = AnalysisContext::default();
+
+        let entry = BlockId(0);
+        let mut cfg = Cfg::new(entry);
+        cfg.add_block(
+            entry,
+            Block {
+                stmts: vec![
+                    Effect::OpenHazard("state".to_string()),
+                    Effect::PanicPoint(Span { line: 30, column: 1 }),
+                ],
+                successors: vec![BlockId(1)],
+            },
+        );
+        cfg.add_block(
+            BlockId(1),
+            Block {
+                stmts: vec![
+                    Effect::OpenHazard("other".to_string()),
+                    Effect::PanicPoint(Span { line: 10, column: 1 }),
+                ],
+                successors: vec![],
+            },
+        );
+
+        let diagnostics = check(&function, &cfg, &panic_facts, &ctx);
+        let spans: Vec<_> = diagnostics.iter().map(|d| (d.span.line, d.span.column)).collect();
+        assert_eq!(spans, vec![(10, 1), (30, 1)]);
+    }
+}
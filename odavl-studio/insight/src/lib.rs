@@ -0,0 +1,18 @@
+//! Static analysis passes backing odavl insight.
+//!
+//! `model` holds the shared call-graph/span representation, `cfg` the
+//! shared control-flow-graph scaffolding, `diagnostics` the shared
+//! reporting type, and `source` the front end that lowers Rust source into
+//! them; each remaining module is one analysis pass over that shared model.
+
+pub mod cfg;
+pub mod checked_arithmetic;
+pub mod diagnostics;
+pub mod exception_safety;
+pub mod lock_poisoning;
+pub mod model;
+pub mod panic_analysis;
+pub mod panic_strategy;
+pub mod recoverable_refactor;
+pub mod source;
+pub mod unreachable_code;
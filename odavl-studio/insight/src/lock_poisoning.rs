@@ -0,0 +1,270 @@
+//! Lock-poisoning lint.
+//!
+//! Flags a `Mutex`/`RwLock` guard whose live range spans a call the
+//! panic-propagation pass ([`crate::panic_analysis`]) marks as possibly
+//! panicking: an unwind while the guard is held poisons the lock and
+//! forces every other thread to handle `PoisonError`.
+//!
+//! Guard liveness is a dataflow analysis over the CFG, not a source-span
+//! comparison: `IN[block]` is the join (set union) of every
+//! predecessor's `OUT`, computed to a fixed point over the block graph.
+//! That's what makes it correct on branchy code — a guard locked on one
+//! branch and read at a merge point is still live there because *some*
+//! predecessor path left it held, and a guard already dropped earlier on
+//! a path isn't wrongly counted as live just because the drop and the
+//! call happen to land in different basic blocks. Mirrors
+//! [`crate::exception_safety`]'s hazard-liveness dataflow.
+
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+use crate::diagnostics::{Diagnostic, Severity, Suggestion};
+use crate::model::{FunctionId, Span};
+use crate::panic_analysis::PanicFacts;
+use crate::panic_strategy::{AnalysisContext, PanicStrategy};
+
+pub use crate::cfg::BlockId;
+
+/// One effect a block has on guard liveness, in program order.
+#[derive(Debug, Clone)]
+pub enum Effect {
+    /// A guard binding coming into scope, e.g. `let guard = mutex.lock()`.
+    Lock(String),
+    /// That binding's drop point (end of scope, unless dropped early).
+    Drop(String),
+    /// A call site; `check` reports it only when the panic-propagation
+    /// pass marks `callee` as possibly panicking.
+    PanicCapableCall { span: Span, callee: FunctionId },
+}
+
+pub type Block = crate::cfg::Block<Effect>;
+pub type Cfg = crate::cfg::Cfg<Effect>;
+
+/// The lattice value at a program point: which guard bindings are
+/// currently held (locked but not yet dropped). Join is set union.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HeldGuards(BTreeSet<String>);
+
+impl HeldGuards {
+    pub fn join(&mut self, other: &HeldGuards) {
+        self.0.extend(other.0.iter().cloned());
+    }
+}
+
+/// Applies one effect's guard-liveness update in place. Shared by the
+/// fixed-point transfer function and the final diagnosing walk, so the
+/// two can't silently drift apart on what locks or drops a guard.
+fn apply_effect(held: &mut BTreeSet<String>, effect: &Effect) {
+    match effect {
+        Effect::Lock(binding) => {
+            held.insert(binding.clone());
+        }
+        Effect::Drop(binding) => {
+            held.remove(binding);
+        }
+        Effect::PanicCapableCall { .. } => {}
+    }
+}
+
+/// Applies a block's effects to an incoming `HeldGuards`.
+fn transfer(block: &Block, input: &HeldGuards) -> HeldGuards {
+    let mut held = input.clone();
+    for effect in &block.stmts {
+        apply_effect(&mut held.0, effect);
+    }
+    held
+}
+
+/// Runs the guard-liveness dataflow to a fixed point, then walks each
+/// block once more from its fixed-point `IN` to report every
+/// panic-capable call reached with a non-empty held set.
+///
+/// Under `panic = "abort"` (per `ctx`) poisoning is moot — the process
+/// terminates on panic instead of leaving a poisoned lock for another
+/// thread to trip over — so this pass reports nothing in that mode.
+///
+/// Diagnostics are returned sorted by source position: block iteration
+/// order isn't deterministic (`Cfg::blocks` is a `HashMap`).
+pub fn check(function: &FunctionId, cfg: &Cfg, panic_facts: &PanicFacts, ctx: &AnalysisContext) -> Vec<Diagnostic> {
+    if ctx.panic_strategy == PanicStrategy::Abort {
+        return Vec::new();
+    }
+
+    let mut inputs: HashMap<BlockId, HeldGuards> =
+        cfg.blocks.keys().map(|&id| (id, HeldGuards::default())).collect();
+    let mut worklist: VecDeque<BlockId> = cfg.blocks.keys().copied().collect();
+
+    while let Some(id) = worklist.pop_front() {
+        let Some(block) = cfg.blocks.get(&id) else { continue };
+        let out = transfer(block, &inputs[&id]);
+
+        for &succ in &block.successors {
+            let Some(succ_in) = inputs.get(&succ).cloned() else { continue };
+            let mut joined = succ_in.clone();
+            joined.join(&out);
+            if joined != succ_in {
+                inputs.insert(succ, joined);
+                worklist.push_back(succ);
+            }
+        }
+    }
+
+    let mut diagnostics = Vec::new();
+    for (id, block) in &cfg.blocks {
+        let mut held = inputs[id].clone();
+        for effect in &block.stmts {
+            if let Effect::PanicCapableCall { span, callee } = effect {
+                if panic_facts.may_panic(callee) {
+                    for binding in &held.0 {
+                        diagnostics.push(
+                            Diagnostic::new(
+                                function.clone(),
+                                *span,
+                                Severity::Warning,
+                                format!(
+                                    "`{binding}` is held across a call to `{callee}`, which can panic and poison the lock"
+                                ),
+                            )
+                            .with_suggestion(Suggestion {
+                                message: "narrow the critical section so the panic-capable call happens after the guard is dropped".to_string(),
+                                replacement: format!("drop({binding}); {callee}(..);"),
+                            })
+                            .with_suggestion(Suggestion {
+                                message: "switch to a non-poisoning primitive (e.g. `parking_lot::Mutex`)".to_string(),
+                                replacement: "parking_lot::Mutex::new(..)".to_string(),
+                            }),
+                        );
+                    }
+                }
+            }
+            apply_effect(&mut held.0, effect);
+        }
+    }
+
+    diagnostics.sort_by_key(|d| (d.span.line, d.span.column));
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::model::CallGraph;
+    use crate::panic_analysis::{self, LocalPanicFacts, PanicOrigin};
+
+    /// Mirrors the request's example: a guard held across a call to
+    /// `helper::process_data`, which the propagation pass has already
+    /// marked as panicking via its own `panic!`.
+    fn panicking_process_data() -> (FunctionId, PanicFacts) {
+        let process_data = FunctionId::new("helper::process_data");
+        let mut graph = CallGraph::new();
+        graph.add_function(process_data.clone());
+        let mut local = HashMap::new();
+        local.insert(
+            process_data.clone(),
+            LocalPanicFacts { origins: vec![(Span { line: 11, column: 5 }, PanicOrigin::ExplicitPanic)] },
+        );
+        (process_data.clone(), panic_analysis::propagate(&graph, &local))
+    }
+
+    #[test]
+    fn flags_guard_held_across_panicking_call() {
+        let (process_data, facts) = panicking_process_data();
+        let function = FunctionId::new("main");
+
+        let entry = BlockId(0);
+        let mut cfg = Cfg::new(entry);
+        cfg.add_block(
+            entry,
+            Block {
+                stmts: vec![
+                    Effect::Lock("guard".to_string()),
+                    Effect::PanicCapableCall { span: Span { line: 5, column: 1 }, callee: process_data },
+                    Effect::Drop("guard".to_string()),
+                ],
+                successors: vec![],
+            },
+        );
+
+        let diagnostics = check(&function, &cfg, &facts, &AnalysisContext::default());
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("guard"));
+    }
+
+    #[test]
+    fn call_after_the_guard_is_dropped_is_not_flagged() {
+        let (process_data, facts) = panicking_process_data();
+        let function = FunctionId::new("main");
+
+        let entry = BlockId(0);
+        let mut cfg = Cfg::new(entry);
+        cfg.add_block(
+            entry,
+            Block {
+                stmts: vec![
+                    Effect::Lock("guard".to_string()),
+                    Effect::Drop("guard".to_string()),
+                    Effect::PanicCapableCall { span: Span { line: 5, column: 1 }, callee: process_data },
+                ],
+                successors: vec![],
+            },
+        );
+
+        assert!(check(&function, &cfg, &facts, &AnalysisContext::default()).is_empty());
+    }
+
+    /// A guard locked on one branch of an `if` and not on the other, both
+    /// merging into a block with a panic-capable call: a span-ordered
+    /// comparison has no notion of branches, but the real fixed-point
+    /// join must mark the guard live at the merge because *some*
+    /// predecessor path left it held.
+    #[test]
+    fn guard_locked_on_one_branch_is_held_at_the_merge() {
+        let (process_data, facts) = panicking_process_data();
+        let function = FunctionId::new("main");
+
+        let entry = BlockId(0);
+        let locks = BlockId(1);
+        let does_not_lock = BlockId(2);
+        let merge = BlockId(3);
+
+        let mut cfg = Cfg::new(entry);
+        cfg.add_block(entry, Block { stmts: vec![], successors: vec![locks, does_not_lock] });
+        cfg.add_block(locks, Block { stmts: vec![Effect::Lock("guard".to_string())], successors: vec![merge] });
+        cfg.add_block(does_not_lock, Block { stmts: vec![], successors: vec![merge] });
+        cfg.add_block(
+            merge,
+            Block {
+                stmts: vec![Effect::PanicCapableCall { span: Span { line: 20, column: 1 }, callee: process_data }],
+                successors: vec![],
+            },
+        );
+
+        let diagnostics = check(&function, &cfg, &facts, &AnalysisContext::default());
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("guard"));
+    }
+
+    #[test]
+    fn abort_strategy_suppresses_poisoning_diagnostics() {
+        let (process_data, facts) = panicking_process_data();
+        let function = FunctionId::new("main");
+
+        let entry = BlockId(0);
+        let mut cfg = Cfg::new(entry);
+        cfg.add_block(
+            entry,
+            Block {
+                stmts: vec![
+                    Effect::Lock("guard".to_string()),
+                    Effect::PanicCapableCall { span: Span { line: 5, column: 1 }, callee: process_data },
+                    Effect::Drop("guard".to_string()),
+                ],
+                successors: vec![],
+            },
+        );
+
+        let ctx = AnalysisContext { panic_strategy: PanicStrategy::Abort };
+        assert!(check(&function, &cfg, &facts, &ctx).is_empty());
+    }
+}
@@ -0,0 +1,77 @@
+//! Shared intermediate representation used across insight's analysis passes.
+//!
+//! This is deliberately small: just enough structure (functions, call
+//! sites, and source spans) for passes to walk without each one owning
+//! its own notion of "where in the crate am I".
+
+use std::collections::HashMap;
+
+/// Stable identifier for a function within the crate being analyzed,
+/// e.g. `helper::process_data`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FunctionId(pub String);
+
+impl FunctionId {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self(path.into())
+    }
+}
+
+impl std::fmt::Display for FunctionId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A location in the source being analyzed, used to anchor diagnostics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub line: u32,
+    pub column: u32,
+}
+
+/// A call from one function to another, at a known source location.
+#[derive(Debug, Clone)]
+pub struct CallSite {
+    pub callee: FunctionId,
+    pub span: Span,
+}
+
+/// The call graph of the crate being analyzed: for each function, the set
+/// of call sites it contains.
+#[derive(Debug, Default)]
+pub struct CallGraph {
+    calls: HashMap<FunctionId, Vec<CallSite>>,
+}
+
+impl CallGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_function(&mut self, function: FunctionId) {
+        self.calls.entry(function).or_default();
+    }
+
+    pub fn add_call(&mut self, caller: FunctionId, call: CallSite) {
+        self.calls.entry(caller).or_default().push(call);
+    }
+
+    pub fn functions(&self) -> impl Iterator<Item = &FunctionId> {
+        self.calls.keys()
+    }
+
+    pub fn calls_from(&self, function: &FunctionId) -> &[CallSite] {
+        self.calls.get(function).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Callers of `callee`, i.e. the reverse edges of the graph. Fixed-point
+    /// passes use this to decide which nodes to revisit.
+    pub fn callers_of(&self, callee: &FunctionId) -> Vec<FunctionId> {
+        self.calls
+            .iter()
+            .filter(|(_, sites)| sites.iter().any(|s| &s.callee == callee))
+            .map(|(caller, _)| caller.clone())
+            .collect()
+    }
+}
@@ -0,0 +1,187 @@
+//! Interprocedural "may panic" analysis.
+//!
+//! Local passes flag `panic!`, `.unwrap()`, and out-of-bounds indexing at
+//! the statement that contains them, but that misses the case where a
+//! function is panic-free on its own yet calls something that isn't. This
+//! pass builds a call graph and propagates a "may panic" fact across it: a
+//! function is panicking if it directly contains a panic source, or
+//! transitively calls another panicking function. Other passes (exception
+//! safety, lock poisoning) query the result instead of redoing the walk.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::diagnostics::{Diagnostic, Severity};
+use crate::model::{CallGraph, FunctionId, Span};
+
+/// Why a given statement can panic.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PanicOrigin {
+    ExplicitPanic,
+    UnwrapOrExpect,
+    SliceIndex,
+    IntegerDivision,
+    UncheckedArithmetic,
+    /// Reached only through a call to another panicking function.
+    Transitive { via: FunctionId },
+}
+
+/// The panic sources found directly inside a function, before any
+/// interprocedural propagation.
+#[derive(Debug, Clone, Default)]
+pub struct LocalPanicFacts {
+    pub origins: Vec<(Span, PanicOrigin)>,
+}
+
+impl LocalPanicFacts {
+    pub fn may_panic(&self) -> bool {
+        !self.origins.is_empty()
+    }
+}
+
+/// The result of the fixed-point computation: for each function, every
+/// reason it may panic (local or transitive), queryable by other passes.
+#[derive(Debug, Clone, Default)]
+pub struct PanicFacts {
+    origins: HashMap<FunctionId, Vec<(Span, PanicOrigin)>>,
+}
+
+impl PanicFacts {
+    pub fn may_panic(&self, function: &FunctionId) -> bool {
+        self.origins.get(function).is_some_and(|o| !o.is_empty())
+    }
+
+    pub fn origins_for(&self, function: &FunctionId) -> &[(Span, PanicOrigin)] {
+        self.origins.get(function).map(Vec::as_slice).unwrap_or(&[])
+    }
+}
+
+/// Computes [`PanicFacts`] for every function in `graph`, seeded from
+/// `local`, via the standard worklist fixed point: a caller is revisited
+/// whenever one of its callees' fact sets grows, until nothing changes.
+/// Recursive call cycles terminate naturally since the origin set is
+/// bounded by the number of distinct callees.
+pub fn propagate(graph: &CallGraph, local: &HashMap<FunctionId, LocalPanicFacts>) -> PanicFacts {
+    let mut facts: HashMap<FunctionId, Vec<(Span, PanicOrigin)>> = HashMap::new();
+    let mut seen: HashMap<FunctionId, HashSet<PanicOrigin>> = HashMap::new();
+
+    for function in graph.functions() {
+        let origins = local.get(function).cloned().unwrap_or_default().origins;
+        seen.insert(function.clone(), origins.iter().map(|(_, o)| o.clone()).collect());
+        facts.insert(function.clone(), origins);
+    }
+
+    let mut worklist: VecDeque<FunctionId> = graph.functions().cloned().collect();
+    while let Some(function) = worklist.pop_front() {
+        let mut grew = false;
+        for call in graph.calls_from(&function) {
+            if facts.get(&call.callee).is_some_and(|o| !o.is_empty()) {
+                let origin = PanicOrigin::Transitive { via: call.callee.clone() };
+                if seen.get_mut(&function).unwrap().insert(origin.clone()) {
+                    facts.get_mut(&function).unwrap().push((call.span, origin));
+                    grew = true;
+                }
+            }
+        }
+
+        if grew {
+            worklist.extend(graph.callers_of(&function));
+        }
+    }
+
+    PanicFacts { origins: facts }
+}
+
+/// Renders [`PanicFacts`] as diagnostics, one per panic origin (each local
+/// panic source, and each call into a panicking function), so callers get
+/// a propagated trace instead of an isolated warning.
+///
+/// Diagnostics are returned sorted by source position: both `graph`'s
+/// function set and each function's origin map live in a `HashMap`, so
+/// iterating them directly would reorder diagnostics across runs on an
+/// identical crate.
+pub fn diagnose(graph: &CallGraph, facts: &PanicFacts) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for function in graph.functions() {
+        for (span, origin) in facts.origins_for(function) {
+            let message = match origin {
+                PanicOrigin::ExplicitPanic => "this function can panic via an explicit `panic!`".to_string(),
+                PanicOrigin::UnwrapOrExpect => "this function can panic via `.unwrap()`/`.expect()`".to_string(),
+                PanicOrigin::SliceIndex => "this function can panic via out-of-bounds indexing".to_string(),
+                PanicOrigin::IntegerDivision => "this function can panic via integer division".to_string(),
+                PanicOrigin::UncheckedArithmetic => {
+                    "this function can panic via unchecked arithmetic overflow".to_string()
+                }
+                PanicOrigin::Transitive { via } => format!("this function can panic through its call to `{via}`"),
+            };
+            diagnostics.push(Diagnostic::new(function.clone(), *span, Severity::Warning, message));
+        }
+    }
+    diagnostics.sort_by_key(|d| (d.span.line, d.span.column));
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::CallSite;
+
+    /// Mirrors `main` calling `helper::process_data` in the runtime-tests
+    /// fixture: `main` has its own local panic source (`.unwrap()`) and
+    /// also calls a function that panics on its own (`process_data`'s
+    /// `panic!`). Propagation should mark `main` as panicking for both
+    /// reasons, tracing the transitive one back to its call site.
+    #[test]
+    fn propagates_panic_through_call_to_fixture_helper() {
+        let main = FunctionId::new("main");
+        let process_data = FunctionId::new("helper::process_data");
+
+        let mut graph = CallGraph::new();
+        graph.add_function(main.clone());
+        graph.add_function(process_data.clone());
+        graph.add_call(main.clone(), CallSite { callee: process_data.clone(), span: Span { line: 17, column: 5 } });
+
+        let mut local = HashMap::new();
+        local.insert(
+            main.clone(),
+            LocalPanicFacts { origins: vec![(Span { line: 8, column: 17 }, PanicOrigin::UnwrapOrExpect)] },
+        );
+        local.insert(
+            process_data.clone(),
+            LocalPanicFacts { origins: vec![(Span { line: 11, column: 5 }, PanicOrigin::ExplicitPanic)] },
+        );
+
+        let facts = propagate(&graph, &local);
+
+        assert!(facts.may_panic(&main));
+        assert!(facts.may_panic(&process_data));
+        assert!(facts
+            .origins_for(&main)
+            .iter()
+            .any(|(_, origin)| matches!(origin, PanicOrigin::Transitive { via } if *via == process_data)));
+
+        let diagnostics = diagnose(&graph, &facts);
+        assert!(diagnostics.len() >= 2);
+    }
+
+    /// Functions and origins both live in `HashMap`s with no guaranteed
+    /// iteration order; output must still come back sorted by source
+    /// position.
+    #[test]
+    fn diagnostics_are_sorted_by_span_regardless_of_iteration_order() {
+        let a = FunctionId::new("a");
+        let b = FunctionId::new("b");
+
+        let mut graph = CallGraph::new();
+        graph.add_function(a.clone());
+        graph.add_function(b.clone());
+
+        let mut local = HashMap::new();
+        local.insert(a.clone(), LocalPanicFacts { origins: vec![(Span { line: 40, column: 1 }, PanicOrigin::ExplicitPanic)] });
+        local.insert(b.clone(), LocalPanicFacts { origins: vec![(Span { line: 10, column: 1 }, PanicOrigin::ExplicitPanic)] });
+
+        let facts = propagate(&graph, &local);
+        let diagnostics = diagnose(&graph, &facts);
+        let spans: Vec<_> = diagnostics.iter().map(|d| (d.span.line, d.span.column)).collect();
+        assert_eq!(spans, vec![(10, 1), (40, 1)]);
+    }
+}
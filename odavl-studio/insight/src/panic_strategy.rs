@@ -0,0 +1,74 @@
+//! Panic-strategy awareness.
+//!
+//! Reads the `panic = "abort"` vs `"unwind"` setting from the relevant
+//! `[profile.*]` section of `Cargo.toml` and exposes it as part of the
+//! shared analysis context, so individual passes can branch on it. Under
+//! `panic = "abort"`, destructors do not run when a panic fires: any
+//! cleanup the exception-safety and lock-poisoning passes assume happens
+//! via `Drop` is dead code, and they escalate or suppress accordingly.
+
+/// The panic strategy in effect for the profile being analyzed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PanicStrategy {
+    #[default]
+    Unwind,
+    Abort,
+}
+
+/// Shared context threaded through every pass that needs to know the
+/// crate's panic strategy.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AnalysisContext {
+    pub panic_strategy: PanicStrategy,
+}
+
+/// Parses the `panic` key out of `Cargo.toml`'s `[profile.<name>]` table,
+/// falling back to `unwind` (rustc's own default) when the key, the
+/// table, or the whole document is missing or malformed.
+pub fn detect(cargo_toml: &str, profile_name: &str) -> PanicStrategy {
+    let Ok(doc) = cargo_toml.parse::<toml::Value>() else {
+        return PanicStrategy::Unwind;
+    };
+
+    let panic = doc
+        .get("profile")
+        .and_then(|profile| profile.get(profile_name))
+        .and_then(|table| table.get("panic"))
+        .and_then(|v| v.as_str());
+
+    match panic {
+        Some("abort") => PanicStrategy::Abort,
+        _ => PanicStrategy::Unwind,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_abort_from_the_named_profile() {
+        let cargo_toml = r#"
+            [package]
+            name = "rust-sample"
+
+            [profile.release]
+            panic = "abort"
+        "#;
+        assert_eq!(detect(cargo_toml, "release"), PanicStrategy::Abort);
+    }
+
+    #[test]
+    fn defaults_to_unwind_when_panic_key_is_absent() {
+        let cargo_toml = r#"
+            [package]
+            name = "rust-sample"
+        "#;
+        assert_eq!(detect(cargo_toml, "release"), PanicStrategy::Unwind);
+    }
+
+    #[test]
+    fn defaults_to_unwind_on_malformed_toml() {
+        assert_eq!(detect("not valid toml {{{", "release"), PanicStrategy::Unwind);
+    }
+}
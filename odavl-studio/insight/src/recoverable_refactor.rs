@@ -0,0 +1,196 @@
+//! Recoverable-vs-unrecoverable classification and `?`-based refactors.
+//!
+//! Classifies each [`PanicOrigin`](crate::panic_analysis::PanicOrigin) as
+//! recoverable (I/O, parsing, lookups — e.g. `data.get(10).unwrap()`) or
+//! unrecoverable (a logic-invariant `panic!`). Recoverable origins get an
+//! autofix giving the enclosing function a `Result`-returning signature,
+//! one suggestion per error-type strategy; the panicking expression itself
+//! is not rewritten to `?`. The signature change is propagated into every
+//! caller: one that already returns a `Result` gets `?` inserted at its
+//! call to the migrated function; one that does not gets `.unwrap()`
+//! inserted instead, to preserve its current panicking behavior at that
+//! boundary.
+
+use crate::diagnostics::{Diagnostic, Severity, Suggestion};
+use crate::model::{FunctionId, Span};
+use crate::panic_analysis::PanicOrigin;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recoverability {
+    /// The panic indicates an external condition the caller could
+    /// reasonably handle (a missing index, a failed parse, absent I/O).
+    Recoverable,
+    /// The panic indicates a violated logic invariant; there's no
+    /// meaningful recovery, only a clearer diagnostic.
+    Unrecoverable,
+}
+
+pub fn classify(origin: &PanicOrigin) -> Recoverability {
+    match origin {
+        PanicOrigin::UnwrapOrExpect | PanicOrigin::SliceIndex | PanicOrigin::IntegerDivision => {
+            Recoverability::Recoverable
+        }
+        PanicOrigin::ExplicitPanic | PanicOrigin::UncheckedArithmetic | PanicOrigin::Transitive { .. } => {
+            Recoverability::Unrecoverable
+        }
+    }
+}
+
+/// How the refactor should represent the function's new error type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorStrategy {
+    /// Generate a dedicated `enum` with one variant per recoverable site,
+    /// for callers that want to match on failure kind.
+    GeneratedEnum,
+    /// Return `Box<dyn Error>`, for call sites that just want to
+    /// propagate and report.
+    BoxDynError,
+}
+
+/// A caller of a site's enclosing function, at the span of its call
+/// expression, that needs updating once the signature changes.
+#[derive(Debug, Clone)]
+pub struct CallSite {
+    pub caller: FunctionId,
+    pub span: Span,
+    /// Whether `caller` itself already returns a `Result` and so can
+    /// propagate the new failure with `?`, versus staying infallible and
+    /// needing a `.unwrap()` to preserve its current panicking behavior.
+    pub caller_returns_result: bool,
+}
+
+/// A panic site being migrated to `Result`, along with the call sites
+/// that need a matching `?` or `.unwrap()` inserted once the signature
+/// changes.
+#[derive(Debug, Clone)]
+pub struct RecoverableSite {
+    pub function: FunctionId,
+    pub span: Span,
+    pub origin: PanicOrigin,
+    pub call_sites: Vec<CallSite>,
+}
+
+/// Emits the `Result`-refactor autofix for every recoverable site (one
+/// diagnostic with one suggestion per error-type strategy), plus one diagnostic per
+/// call site proposing the matching `?`/`.unwrap()` insertion.
+pub fn suggest(sites: &[RecoverableSite]) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    for site in sites.iter().filter(|site| classify(&site.origin) == Recoverability::Recoverable) {
+        diagnostics.push(
+            Diagnostic::new(
+                site.function.clone(),
+                site.span,
+                Severity::Warning,
+                "this panic is recoverable; consider returning `Result` instead",
+            )
+            .with_suggestion(Suggestion {
+                message: "generate a dedicated error enum with one variant per failure site".to_string(),
+                replacement: result_signature(&site.function, ErrorStrategy::GeneratedEnum),
+            })
+            .with_suggestion(Suggestion {
+                message: "return `Box<dyn std::error::Error>` and propagate with `?`".to_string(),
+                replacement: result_signature(&site.function, ErrorStrategy::BoxDynError),
+            }),
+        );
+
+        for call in &site.call_sites {
+            diagnostics.push(call_site_diagnostic(&site.function, call));
+        }
+    }
+
+    diagnostics
+}
+
+fn call_site_diagnostic(migrated: &FunctionId, call: &CallSite) -> Diagnostic {
+    if call.caller_returns_result {
+        Diagnostic::new(
+            call.caller.clone(),
+            call.span,
+            Severity::Warning,
+            format!("`{}` already returns `Result`; propagate `{migrated}`'s new failure with `?`", call.caller),
+        )
+        .with_suggestion(Suggestion {
+            message: "propagate with `?`".to_string(),
+            replacement: format!("{migrated}(..)?"),
+        })
+    } else {
+        Diagnostic::new(
+            call.caller.clone(),
+            call.span,
+            Severity::Warning,
+            format!(
+                "`{}` does not return `Result`; `.unwrap()` here preserves its current panicking behavior \
+                 (or migrate `{}` to `Result` too)",
+                call.caller, call.caller
+            ),
+        )
+        .with_suggestion(Suggestion {
+            message: "keep the call site panicking with `.unwrap()`".to_string(),
+            replacement: format!("{migrated}(..).unwrap()"),
+        })
+    }
+}
+
+fn result_signature(function: &FunctionId, strategy: ErrorStrategy) -> String {
+    match strategy {
+        ErrorStrategy::GeneratedEnum => format!("fn {function}(..) -> Result<_, {function}Error> {{ .. }}"),
+        ErrorStrategy::BoxDynError => format!("fn {function}(..) -> Result<_, Box<dyn std::error::Error>> {{ .. }}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Mirrors `main`'s `data.get(10).unwrap()` from the runtime-tests
+    /// fixture, called from both a `Result`-returning caller and a
+    /// plain one, to exercise both ends of the `?`/`.unwrap()` choice.
+    fn fixture_site() -> RecoverableSite {
+        RecoverableSite {
+            function: FunctionId::new("main"),
+            span: Span { line: 8, column: 17 },
+            origin: PanicOrigin::UnwrapOrExpect,
+            call_sites: vec![
+                CallSite {
+                    caller: FunctionId::new("run"),
+                    span: Span { line: 30, column: 5 },
+                    caller_returns_result: true,
+                },
+                CallSite {
+                    caller: FunctionId::new("entrypoint"),
+                    span: Span { line: 40, column: 5 },
+                    caller_returns_result: false,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn recoverable_site_gets_both_error_strategy_suggestions() {
+        let diagnostics = suggest(&[fixture_site()]);
+        let main_diagnostic = &diagnostics[0];
+        assert_eq!(main_diagnostic.function, FunctionId::new("main"));
+        assert_eq!(main_diagnostic.suggestions.len(), 2);
+    }
+
+    #[test]
+    fn result_returning_caller_gets_a_question_mark_insertion() {
+        let diagnostics = suggest(&[fixture_site()]);
+        let run_diagnostic = diagnostics.iter().find(|d| d.function == FunctionId::new("run")).unwrap();
+        assert!(run_diagnostic.suggestions[0].replacement.ends_with('?'));
+    }
+
+    #[test]
+    fn infallible_caller_gets_an_unwrap_insertion() {
+        let diagnostics = suggest(&[fixture_site()]);
+        let entry_diagnostic = diagnostics.iter().find(|d| d.function == FunctionId::new("entrypoint")).unwrap();
+        assert!(entry_diagnostic.suggestions[0].replacement.ends_with(".unwrap()"));
+    }
+
+    #[test]
+    fn unrecoverable_origin_is_not_suggested() {
+        let site = RecoverableSite { origin: PanicOrigin::ExplicitPanic, ..fixture_site() };
+        assert!(suggest(&[site]).is_empty());
+    }
+}
@@ -0,0 +1,889 @@
+//! Source-to-model front end.
+//!
+//! Parses real Rust source (via `syn`) into the shared [`crate::model::CallGraph`]
+//! and the per-pass inputs for [`crate::panic_analysis`], [`crate::checked_arithmetic`],
+//! [`crate::unreachable_code`], [`crate::recoverable_refactor`], and (for a
+//! few recognized shapes each) [`crate::lock_poisoning`] and
+//! [`crate::exception_safety`] — the passes whose input is derivable from
+//! syntax alone. [`crate::recoverable_refactor::RecoverableSite`] is built
+//! in a second pass, once the whole crate's call graph and local panic
+//! facts are known: each local panic origin becomes a site, its
+//! `call_sites` come from [`CallGraph::callers_of`], and each caller's
+//! `caller_returns_result` comes from its signature's return type.
+//!
+//! `mod foo;` declarations are resolved to a sibling `foo.rs` (or
+//! `foo/mod.rs`) next to the declaring file, so a multi-file crate like
+//! `runtime-tests/rust-sample` lowers as a whole; `resolve_mod_file` says
+//! where this differs from rustc.
+//!
+//! Within a function body, only the top-level statement sequence is turned
+//! into a [`crate::unreachable_code::Cfg`], a [`crate::lock_poisoning::Cfg`],
+//! or a [`crate::exception_safety::Cfg`]: for the unreachable-code CFG, a
+//! top-level `if <literal bool> { .. }` with no `else` splits into blocks
+//! (modeling the one constant-condition shape the pass cares about); for
+//! the lock-poisoning CFG, a top-level `if <cond> { .. } [else { .. }]` of
+//! any condition splits into a real branch the same way, and within each
+//! branch (or the top-level sequence itself) a `let binding =
+//! <expr>.lock()/.read()/.write()...` becomes a guard coming into scope, a
+//! `drop(binding)` becomes its drop point, a call becomes a candidate
+//! panic site (whether it actually panics is decided later, once
+//! interprocedural facts are available), and any guard still held when its
+//! scope ends gets an implicit drop at that end-of-scope point; for the
+//! exception-safety CFG, a `mem::replace(&mut place, ..)` or
+//! `ptr::write(place, ..)` opens a hazard on `place`, a plain `place =
+//! ..;` assignment closes it, and `unsafe { .. }` blocks are transparent
+//! (their statements are folded into the surrounding sequence rather than
+//! treated as their own scope). Either way, anything a statement might
+//! contain besides these recognized shapes (loops, `match`, `else if`
+//! chains, nested blocks) is treated as a single opaque, always-live
+//! statement rather than walked into. Call sites, panic origins, and
+//! arithmetic expressions, by contrast, are found anywhere in the body via
+//! a full recursive visit, since those facts don't depend on control flow.
+//! This is intentionally a small front end, not a general Rust
+//! control-flow-graph builder.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+
+use crate::checked_arithmetic::{ArithExpr, ArithOp};
+use crate::exception_safety::{Block as ExceptionBlock, Cfg as ExceptionCfg, Effect as ExceptionEffect};
+use crate::lock_poisoning::{Block as LockBlock, Cfg as LockCfg, Effect as LockEffect};
+use crate::model::{CallGraph, CallSite, FunctionId, Span};
+use crate::panic_analysis::{LocalPanicFacts, PanicOrigin};
+use crate::recoverable_refactor::{CallSite as RecoverableCallSite, RecoverableSite};
+use crate::unreachable_code::{BasicBlock, BlockId, BlockStmt, Cfg as UnreachableCfg, DivergingKind};
+
+/// Everything the syntax-derivable passes need, lowered from real source.
+#[derive(Debug, Default)]
+pub struct Lowered {
+    pub graph: CallGraph,
+    pub local_panics: HashMap<FunctionId, LocalPanicFacts>,
+    pub arith_exprs: Vec<ArithExpr>,
+    pub unreachable_cfgs: HashMap<FunctionId, UnreachableCfg>,
+    pub lock_cfgs: HashMap<FunctionId, LockCfg>,
+    pub exception_cfgs: HashMap<FunctionId, ExceptionCfg>,
+    /// Whether each function's declared return type is `Result<_, _>`,
+    /// i.e. whether a caller of it could propagate a new failure with
+    /// `?` instead of needing `.unwrap()`. Keyed by every function found,
+    /// not just ones with recoverable sites.
+    pub returns_result: HashMap<FunctionId, bool>,
+    pub recoverable_sites: Vec<RecoverableSite>,
+}
+
+/// Parses `entry` and every file-backed module it transitively declares,
+/// lowering each function found into the shared model.
+pub fn lower_crate(entry: &Path) -> std::io::Result<Lowered> {
+    let mut lowered = Lowered::default();
+    lower_file(entry, "", &mut lowered)?;
+    lowered.recoverable_sites = build_recoverable_sites(&lowered);
+    Ok(lowered)
+}
+
+/// Turns every local panic origin found anywhere in the crate into a
+/// [`RecoverableSite`], now that the full call graph and every function's
+/// `returns_result` are known. Sorted by function then span, since the
+/// origins come off a `HashMap` whose iteration order isn't deterministic.
+fn build_recoverable_sites(lowered: &Lowered) -> Vec<RecoverableSite> {
+    let mut sites: Vec<RecoverableSite> = lowered
+        .local_panics
+        .iter()
+        .flat_map(|(function, facts)| {
+            facts.origins.iter().map(move |(span, origin)| RecoverableSite {
+                function: function.clone(),
+                span: *span,
+                origin: origin.clone(),
+                call_sites: lowered
+                    .graph
+                    .callers_of(function)
+                    .into_iter()
+                    .map(|caller| {
+                        let call_span = lowered
+                            .graph
+                            .calls_from(&caller)
+                            .iter()
+                            .find(|call| &call.callee == function)
+                            .map_or(*span, |call| call.span);
+                        let caller_returns_result = lowered.returns_result.get(&caller).copied().unwrap_or(false);
+                        RecoverableCallSite { caller, span: call_span, caller_returns_result }
+                    })
+                    .collect(),
+            })
+        })
+        .collect();
+
+    sites.sort_by_key(|site| (site.function.0.clone(), site.span.line, site.span.column));
+    sites
+}
+
+fn lower_file(path: &Path, module_prefix: &str, lowered: &mut Lowered) -> std::io::Result<()> {
+    let source = fs::read_to_string(path)?;
+    let file = syn::parse_file(&source)
+        .unwrap_or_else(|err| panic!("failed to parse {}: {err}", path.display()));
+
+    for item in &file.items {
+        match item {
+            syn::Item::Fn(item_fn) => {
+                let function = FunctionId::new(qualify(module_prefix, &item_fn.sig.ident.to_string()));
+                lowered.graph.add_function(function.clone());
+                lowered.returns_result.insert(function.clone(), returns_result_type(&item_fn.sig));
+                lower_fn_body(&function, &item_fn.sig, &item_fn.block, lowered);
+            }
+            syn::Item::Mod(item_mod) if item_mod.content.is_none() => {
+                if let Some(child_path) = resolve_mod_file(path, &item_mod.ident.to_string()) {
+                    let child_prefix = qualify(module_prefix, &item_mod.ident.to_string());
+                    lower_file(&child_path, &child_prefix, lowered)?;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn qualify(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() {
+        name.to_string()
+    } else {
+        format!("{prefix}::{name}")
+    }
+}
+
+/// `mod foo;` resolves to `foo.rs` next to the declaring file, or
+/// `foo/mod.rs`. That matches rustc for a crate root or a `mod.rs`; for a
+/// `mod foo;` in any other file `bar.rs`, rustc looks in `bar/` instead,
+/// which this lookup does not.
+fn resolve_mod_file(declaring_file: &Path, name: &str) -> Option<PathBuf> {
+    let dir = declaring_file.parent()?;
+    let sibling = dir.join(format!("{name}.rs"));
+    if sibling.is_file() {
+        return Some(sibling);
+    }
+    let nested = dir.join(name).join("mod.rs");
+    nested.is_file().then_some(nested)
+}
+
+fn lower_fn_body(function: &FunctionId, sig: &syn::Signature, block: &syn::Block, lowered: &mut Lowered) {
+    let mut facts = LocalPanicFacts::default();
+    let mut visitor = BodyVisitor { function, lowered, facts: &mut facts, int_types: param_int_types(sig) };
+    visitor.visit_block(block);
+    lowered.local_panics.insert(function.clone(), facts);
+
+    let cfg = lower_cfg(block);
+    lowered.unreachable_cfgs.insert(function.clone(), cfg);
+
+    let lock_cfg = lower_lock_cfg(block);
+    lowered.lock_cfgs.insert(function.clone(), lock_cfg);
+
+    let exception_cfg = lower_exception_cfg(block);
+    lowered.exception_cfgs.insert(function.clone(), exception_cfg);
+}
+
+/// Recursively finds call sites, panic origins, and arithmetic expressions
+/// anywhere in a function body, independent of control flow.
+struct BodyVisitor<'a> {
+    function: &'a FunctionId,
+    lowered: &'a mut Lowered,
+    facts: &'a mut LocalPanicFacts,
+    /// Declared integer type of every binding in scope whose width is
+    /// known, keyed by identifier: seeded from `sig`'s parameters and
+    /// grown as explicitly-typed `let`s are visited. Used to resolve
+    /// `checked_arithmetic`'s `bit_width`/`is_signed` to the operand's
+    /// real declared type instead of a guessed default.
+    int_types: HashMap<String, IntType>,
+}
+
+impl<'a> BodyVisitor<'a> {
+    fn note_if_panic_macro(&mut self, mac: &syn::Macro) {
+        if path_string(&mac.path) == "panic" {
+            self.facts.origins.push((to_span(mac.path.segments[0].ident.span()), PanicOrigin::ExplicitPanic));
+        }
+    }
+}
+
+impl<'ast, 'a> Visit<'ast> for BodyVisitor<'a> {
+    fn visit_expr_call(&mut self, call: &'ast syn::ExprCall) {
+        if let syn::Expr::Path(path) = &*call.func {
+            let callee = FunctionId::new(path_string(&path.path));
+            let span = to_span(path.path.segments.last().unwrap().ident.span());
+            self.lowered.graph.add_call(self.function.clone(), CallSite { callee, span });
+        }
+        visit::visit_expr_call(self, call);
+    }
+
+    fn visit_expr_method_call(&mut self, call: &'ast syn::ExprMethodCall) {
+        let method = call.method.to_string();
+        if method == "unwrap" || method == "expect" {
+            self.facts.origins.push((to_span(call.method.span()), PanicOrigin::UnwrapOrExpect));
+        }
+        visit::visit_expr_method_call(self, call);
+    }
+
+    fn visit_expr_macro(&mut self, expr: &'ast syn::ExprMacro) {
+        self.note_if_panic_macro(&expr.mac);
+        visit::visit_expr_macro(self, expr);
+    }
+
+    // `panic!(...);` in statement position parses as `Stmt::Macro`, not
+    // `Stmt::Expr(Expr::Macro(..))`, so it needs its own visit method —
+    // `visit_expr_macro` alone misses it.
+    fn visit_stmt_macro(&mut self, stmt: &'ast syn::StmtMacro) {
+        self.note_if_panic_macro(&stmt.mac);
+        visit::visit_stmt_macro(self, stmt);
+    }
+
+    fn visit_expr_index(&mut self, expr: &'ast syn::ExprIndex) {
+        self.facts.origins.push((to_span(expr.span()), PanicOrigin::SliceIndex));
+        visit::visit_expr_index(self, expr);
+    }
+
+    fn visit_expr_binary(&mut self, expr: &'ast syn::ExprBinary) {
+        let span = to_span(expr.op.span());
+        match &expr.op {
+            // `x / -1` panics on overflow when `x` is the type's minimum
+            // value, since the mathematical result (`-x`) doesn't fit —
+            // there's no positive counterpart to negate into. `x % -1`
+            // panics for that same `x` too ("attempt to calculate the
+            // remainder with overflow"), so both `Div` and `Rem` need this
+            // beyond the zero-divisor check below.
+            syn::BinOp::Div(_) | syn::BinOp::Rem(_) if int_literal_value(&expr.right) == Some(-1) => {
+                self.facts.origins.push((span, PanicOrigin::IntegerDivision));
+            }
+            syn::BinOp::Div(_) | syn::BinOp::Rem(_) if !is_nonzero_int_literal(&expr.right) => {
+                self.facts.origins.push((span, PanicOrigin::IntegerDivision));
+            }
+            op => {
+                if let Some(arith_op) = arith_op_of(op) {
+                    // `provably_safe` treats `const_operand` as the *right*
+                    // operand (the amount subtracted, the shift amount,
+                    // ...), which only holds for `Sub`/`Shl` if the literal
+                    // is actually on the right: `1u32 << n` and `0 - x`
+                    // both have their constant on the left, and neither
+                    // one says anything about whether the runtime operand
+                    // overflows. `Add`/`Mul` are commutative, so either
+                    // side is fine there.
+                    let const_operand = match arith_op {
+                        ArithOp::Add | ArithOp::Mul => {
+                            int_literal_value(&expr.right).or_else(|| int_literal_value(&expr.left))
+                        }
+                        ArithOp::Sub | ArithOp::Shl => int_literal_value(&expr.right),
+                    };
+                    // The declared type comes from whichever operand resolves
+                    // (a cast, a literal suffix, or a parameter/`let` this
+                    // visitor has already recorded the type of): for `Shl`
+                    // that's always the left operand (the value being
+                    // shifted — the right operand is just a shift amount,
+                    // often of a different, irrelevant type), for the
+                    // commutative/same-typed `Add`/`Sub`/`Mul` either side
+                    // will do. When neither side resolves, fall back to
+                    // `i32`, the most common default-inferred integer type.
+                    let int_type = match arith_op {
+                        ArithOp::Shl => expr_int_type(&expr.left, &self.int_types),
+                        ArithOp::Add | ArithOp::Sub | ArithOp::Mul => expr_int_type(&expr.left, &self.int_types)
+                            .or_else(|| expr_int_type(&expr.right, &self.int_types)),
+                    }
+                    .unwrap_or_default();
+                    let arith_expr = ArithExpr {
+                        function: self.function.clone(),
+                        span,
+                        op: arith_op,
+                        lhs: expr_to_string(&expr.left),
+                        lhs_needs_parens: needs_parens_as_receiver(&expr.left),
+                        rhs: expr_to_string(&expr.right),
+                        const_operand,
+                        bit_width: int_type.bit_width,
+                        is_signed: int_type.is_signed,
+                    };
+                    if !crate::checked_arithmetic::lint(std::slice::from_ref(&arith_expr)).is_empty() {
+                        self.facts.origins.push((span, PanicOrigin::UncheckedArithmetic));
+                    }
+                    self.lowered.arith_exprs.push(arith_expr);
+                }
+            }
+        }
+        visit::visit_expr_binary(self, expr);
+    }
+
+    // An explicitly-typed `let x: u8 = ..;` grows `int_types` the same
+    // way a parameter seeds it, so a binary expression using `x` later in
+    // the body resolves its real declared width instead of the fallback.
+    fn visit_local(&mut self, local: &'ast syn::Local) {
+        if let syn::Pat::Type(pat_type) = &local.pat {
+            if let (syn::Pat::Ident(pat_ident), Some(int_type)) =
+                (&*pat_type.pat, int_type_of_syn_type(&pat_type.ty))
+            {
+                self.int_types.insert(pat_ident.ident.to_string(), int_type);
+            }
+        }
+        visit::visit_local(self, local);
+    }
+}
+
+/// An integer operand's declared width and signedness, e.g. `(8, false)`
+/// for `u8`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct IntType {
+    bit_width: u32,
+    is_signed: bool,
+}
+
+impl Default for IntType {
+    /// The fallback when no declared type can be resolved: a plain
+    /// `i32`, the most common default-inferred integer type.
+    fn default() -> Self {
+        IntType { bit_width: 32, is_signed: true }
+    }
+}
+
+/// The parameters of `sig` whose declared type is a plain integer type,
+/// keyed by parameter name — the initial contents of a [`BodyVisitor`]'s
+/// `int_types` map, before any `let` in the body grows it further.
+fn param_int_types(sig: &syn::Signature) -> HashMap<String, IntType> {
+    let mut types = HashMap::new();
+    for arg in &sig.inputs {
+        if let syn::FnArg::Typed(pat_type) = arg {
+            if let (syn::Pat::Ident(pat_ident), Some(int_type)) =
+                (&*pat_type.pat, int_type_of_syn_type(&pat_type.ty))
+            {
+                types.insert(pat_ident.ident.to_string(), int_type);
+            }
+        }
+    }
+    types
+}
+
+/// The [`IntType`] a plain integer type name (`i8`, `u64`, `usize`, ...)
+/// denotes, or `None` for anything else (a generic, a struct, ...).
+/// `isize`/`usize` are treated as 64-bit, matching every platform this
+/// front end's own test fixtures target.
+fn int_type_of_ident(name: &str) -> Option<IntType> {
+    let (bit_width, is_signed) = match name {
+        "i8" => (8, true),
+        "i16" => (16, true),
+        "i32" => (32, true),
+        "i64" => (64, true),
+        "i128" => (128, true),
+        "isize" => (64, true),
+        "u8" => (8, false),
+        "u16" => (16, false),
+        "u32" => (32, false),
+        "u64" => (64, false),
+        "u128" => (128, false),
+        "usize" => (64, false),
+        _ => return None,
+    };
+    Some(IntType { bit_width, is_signed })
+}
+
+fn int_type_of_syn_type(ty: &syn::Type) -> Option<IntType> {
+    let syn::Type::Path(type_path) = ty else { return None };
+    int_type_of_ident(&type_path.path.segments.last()?.ident.to_string())
+}
+
+/// Whether `sig`'s declared return type is `Result<_, _>` (however it's
+/// spelled — `Result`, `std::result::Result`, a type alias to it is not
+/// detected, same limitation as everywhere else this front end matches on
+/// a type's last path segment by name rather than resolving it).
+fn returns_result_type(sig: &syn::Signature) -> bool {
+    let syn::ReturnType::Type(_, ty) = &sig.output else { return false };
+    let syn::Type::Path(type_path) = &**ty else { return false };
+    type_path.path.segments.last().is_some_and(|segment| segment.ident == "Result")
+}
+
+/// Resolves `expr`'s declared integer type where it's derivable from
+/// syntax alone: a cast's target type, an integer literal's suffix, or a
+/// path already recorded in `int_types` (a parameter or explicitly-typed
+/// `let`). Anything else (a method call's return type, field access,
+/// ...) isn't resolved, since that needs real type inference this front
+/// end doesn't attempt.
+fn expr_int_type(expr: &syn::Expr, int_types: &HashMap<String, IntType>) -> Option<IntType> {
+    match expr {
+        syn::Expr::Paren(inner) => expr_int_type(&inner.expr, int_types),
+        syn::Expr::Group(inner) => expr_int_type(&inner.expr, int_types),
+        syn::Expr::Unary(unary) if matches!(unary.op, syn::UnOp::Neg(_)) => expr_int_type(&unary.expr, int_types),
+        syn::Expr::Cast(cast) => int_type_of_syn_type(&cast.ty),
+        syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Int(int), .. }) => {
+            let suffix = int.suffix();
+            if suffix.is_empty() { None } else { int_type_of_ident(suffix) }
+        }
+        syn::Expr::Path(path) => int_types.get(&path_string(&path.path)).copied(),
+        _ => None,
+    }
+}
+
+fn arith_op_of(op: &syn::BinOp) -> Option<ArithOp> {
+    match op {
+        syn::BinOp::Add(_) => Some(ArithOp::Add),
+        syn::BinOp::Sub(_) => Some(ArithOp::Sub),
+        syn::BinOp::Mul(_) => Some(ArithOp::Mul),
+        syn::BinOp::Shl(_) => Some(ArithOp::Shl),
+        _ => None,
+    }
+}
+
+fn is_nonzero_int_literal(expr: &syn::Expr) -> bool {
+    int_literal_value(expr).is_some_and(|v| v != 0)
+}
+
+fn int_literal_value(expr: &syn::Expr) -> Option<i128> {
+    // A negative integer literal doesn't parse as a single `Expr::Lit` —
+    // `-1` is a unary negation of the literal `1` — so unwrap that layer
+    // before falling through to the plain literal case.
+    if let syn::Expr::Unary(syn::ExprUnary { op: syn::UnOp::Neg(_), expr: inner, .. }) = expr {
+        return int_literal_value(inner).map(|v| -v);
+    }
+    let syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Int(int), .. }) = expr else { return None };
+    int.base10_parse::<i128>().ok()
+}
+
+/// Renders `expr` to source text, compacted the way a hand-written
+/// autofix would be: no padding around brackets or `::`, but a single
+/// space is kept wherever dropping it would fuse two word tokens into
+/// one (e.g. the `as` in a cast, or two adjacent keywords) into garbage
+/// that no longer tokenizes the same way — `to_token_stream().to_string()`
+/// pads every token, so a blind `.replace(' ', "")` turns `x as i64` into
+/// the single identifier `xasi64`.
+fn expr_to_string(expr: &syn::Expr) -> String {
+    use quote::ToTokens;
+    tokens_to_compact_string(expr.to_token_stream())
+}
+
+fn tokens_to_compact_string(tokens: proc_macro2::TokenStream) -> String {
+    let mut out = String::new();
+    for tree in tokens {
+        let piece = match &tree {
+            proc_macro2::TokenTree::Group(group) => {
+                let (open, close) = match group.delimiter() {
+                    proc_macro2::Delimiter::Parenthesis => ("(", ")"),
+                    proc_macro2::Delimiter::Brace => ("{", "}"),
+                    proc_macro2::Delimiter::Bracket => ("[", "]"),
+                    proc_macro2::Delimiter::None => ("", ""),
+                };
+                format!("{open}{}{close}", tokens_to_compact_string(group.stream()))
+            }
+            _ => tree.to_string(),
+        };
+        if out.chars().last().is_some_and(is_word_char) && piece.chars().next().is_some_and(is_word_char) {
+            out.push(' ');
+        }
+        out.push_str(&piece);
+    }
+    out
+}
+
+/// Whether `c` can be part of an identifier or a literal, i.e. whether
+/// joining two tokens without a separator would let them merge into one.
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Whether `expr`'s rendered text needs wrapping in parens before a
+/// method call is appended to it, e.g. in a `checked_arithmetic` autofix
+/// suggestion. A method call binds tighter than any binary or unary
+/// operator, so anything other than an already-atomic postfix chain
+/// (a path, a literal, `a[i]`, `a.b`, `f(x)`, `x.f()`, an already-
+/// parenthesized group, ...) needs parens to keep the method call
+/// applying to the whole expression rather than just its last operand.
+fn needs_parens_as_receiver(expr: &syn::Expr) -> bool {
+    !matches!(
+        expr,
+        syn::Expr::Path(_)
+            | syn::Expr::Lit(_)
+            | syn::Expr::Paren(_)
+            | syn::Expr::Index(_)
+            | syn::Expr::Call(_)
+            | syn::Expr::MethodCall(_)
+            | syn::Expr::Field(_)
+            | syn::Expr::Macro(_)
+            | syn::Expr::Array(_)
+            | syn::Expr::Tuple(_)
+    )
+}
+
+fn path_string(path: &syn::Path) -> String {
+    path.segments.iter().map(|s| s.ident.to_string()).collect::<Vec<_>>().join("::")
+}
+
+/// Converts a `proc_macro2::Span`'s start position into our own 1-based
+/// [`Span`], since every pass's diagnostics are anchored by line/column
+/// rather than by the token span types the parser uses internally.
+fn to_span(span: proc_macro2::Span) -> Span {
+    let start = span.start();
+    Span { line: start.line as u32, column: start.column as u32 + 1 }
+}
+
+/// Lowers a function body's top-level statement sequence into an
+/// [`UnreachableCfg`]; see the module doc comment for what this does and
+/// doesn't model.
+fn lower_cfg(block: &syn::Block) -> UnreachableCfg {
+    let mut cfg = UnreachableCfg::new(BlockId(0));
+    let mut next_id = 1u32;
+    lower_stmts(&mut cfg, &mut next_id, BlockId(0), &block.stmts, None);
+    cfg
+}
+
+fn fresh_id(next_id: &mut u32) -> BlockId {
+    let id = BlockId(*next_id);
+    *next_id += 1;
+    id
+}
+
+fn lower_stmts(
+    cfg: &mut UnreachableCfg,
+    next_id: &mut u32,
+    mut current_id: BlockId,
+    stmts: &[syn::Stmt],
+    after: Option<BlockId>,
+) {
+    let mut current_stmts: Vec<BlockStmt> = Vec::new();
+
+    for stmt in stmts {
+        if let Some((cond, then_block)) = const_if(stmt) {
+            cfg.add_block(current_id, BasicBlock { stmts: std::mem::take(&mut current_stmts), successors: vec![] });
+
+            let then_id = fresh_id(next_id);
+            let after_id = fresh_id(next_id);
+            // There's no `else` in the source, but `after_id` is exactly
+            // where control goes when the (absent) else is implicitly
+            // taken, so it plays the `else_block` role here: for `cond ==
+            // false` that's the only live edge out of `current_id` (the
+            // then-branch is statically dead), and for `cond == true`
+            // `add_if_edges` ignores it anyway, since `then_id`'s own
+            // lowering already links forward to `after_id`.
+            cfg.add_if_edges(current_id, Some(cond), then_id, Some(after_id));
+            lower_stmts(cfg, next_id, then_id, &then_block.stmts, Some(after_id));
+
+            current_id = after_id;
+            continue;
+        }
+
+        current_stmts.push(BlockStmt { span: stmt_span(stmt), diverges: stmt_diverges(stmt) });
+    }
+
+    // The edge to `after` is kept even when this block diverges:
+    // `reachable_blocks` never follows it, but `dominating_divergence`
+    // walks it backward to point the dead code after the `if` at this
+    // block's diverging statement.
+    cfg.add_block(current_id, BasicBlock { stmts: current_stmts, successors: after.into_iter().collect() });
+}
+
+/// A top-level `if <literal bool> { .. }` with no `else` — the one
+/// constant-condition shape this front end models as a real branch.
+fn const_if(stmt: &syn::Stmt) -> Option<(bool, &syn::Block)> {
+    let syn::Stmt::Expr(syn::Expr::If(expr_if), _) = stmt else { return None };
+    if expr_if.else_branch.is_some() {
+        return None;
+    }
+    let syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Bool(b), .. }) = &*expr_if.cond else { return None };
+    Some((b.value, &expr_if.then_branch))
+}
+
+fn stmt_diverges(stmt: &syn::Stmt) -> Option<DivergingKind> {
+    // `panic!(...);` in statement position parses as `Stmt::Macro`, not
+    // `Stmt::Expr(Expr::Macro(..))` — check that shape too.
+    if let syn::Stmt::Macro(stmt_macro) = stmt {
+        return (path_string(&stmt_macro.mac.path) == "panic").then_some(DivergingKind::Panic);
+    }
+    let syn::Stmt::Expr(expr, _) = stmt else { return None };
+    match expr {
+        syn::Expr::Macro(m) if path_string(&m.mac.path) == "panic" => Some(DivergingKind::Panic),
+        syn::Expr::Return(_) => Some(DivergingKind::Return),
+        syn::Expr::Break(_) | syn::Expr::Continue(_) => Some(DivergingKind::BreakOrContinue),
+        syn::Expr::Call(call) if is_process_exit(&call.func) => Some(DivergingKind::ProcessExit),
+        syn::Expr::Loop(expr_loop) => (!loop_has_break(expr_loop)).then_some(DivergingKind::InfiniteLoopWithNoBreak),
+        _ => None,
+    }
+}
+
+fn is_process_exit(func: &syn::Expr) -> bool {
+    // This front end doesn't resolve `use` imports, so a bare `exit(..)`
+    // brought in via `use std::process::exit;` can't be distinguished
+    // from some unrelated local `exit` function by path alone. Matching
+    // the bare ident anyway is the safe direction for a dead-code lint:
+    // it only risks treating a non-diverging call as diverging in the
+    // (rare) case of a same-named function, and the alternative is
+    // silently missing the common import form entirely.
+    matches!(func, syn::Expr::Path(p) if matches!(
+        path_string(&p.path).as_str(),
+        "std::process::exit" | "process::exit" | "exit"
+    ))
+}
+
+/// Whether a `loop { .. }` can ever exit on its own, i.e. its body
+/// contains a `break` anywhere. Unlike `stmt_diverges`'s other cases this
+/// recurses into the loop body rather than staying at statement level,
+/// since "does this loop ever break" isn't decidable from the `loop`
+/// keyword alone. A `break` found inside a nested loop or closure is
+/// counted too, even though it may actually target the inner construct
+/// rather than this one — that's the safe direction for a dead-code
+/// lint: treating a live loop as non-diverging only risks a missed
+/// diagnostic, while the other way round would flag reachable code as
+/// dead.
+fn loop_has_break(expr_loop: &syn::ExprLoop) -> bool {
+    struct BreakFinder(bool);
+
+    impl<'ast> Visit<'ast> for BreakFinder {
+        fn visit_expr_break(&mut self, expr: &'ast syn::ExprBreak) {
+            self.0 = true;
+            visit::visit_expr_break(self, expr);
+        }
+    }
+
+    let mut finder = BreakFinder(false);
+    finder.visit_block(&expr_loop.body);
+    finder.0
+}
+
+fn stmt_span(stmt: &syn::Stmt) -> Span {
+    to_span(stmt.span())
+}
+
+/// Lowers a function body's top-level statement sequence into a
+/// [`LockCfg`] for [`crate::lock_poisoning`]: see the module doc comment
+/// for exactly which shapes become `Lock`/`Drop`/`PanicCapableCall`
+/// effects. Like `lower_cfg`, a top-level `if { .. } [else { .. }]`
+/// becomes a real branch (via `Cfg::add_if_edges`, reusing the same
+/// machinery `lower_cfg` uses) — unlike `lower_cfg`, the condition need
+/// not be a literal bool, since guard liveness cares about which paths
+/// exist, not which one actually runs. A guard locked inside a branch and
+/// never explicitly dropped gets its implicit end-of-scope drop at the
+/// end of *that* branch, matching Rust's real drop scoping, rather than
+/// being carried past the merge point.
+fn lower_lock_cfg(block: &syn::Block) -> LockCfg {
+    let mut cfg = LockCfg::new(BlockId(0));
+    let mut next_id = 1u32;
+    lower_lock_stmts(&mut cfg, &mut next_id, BlockId(0), &block.stmts, None);
+    cfg
+}
+
+fn lower_lock_stmts(
+    cfg: &mut LockCfg,
+    next_id: &mut u32,
+    mut current_id: BlockId,
+    stmts: &[syn::Stmt],
+    after: Option<BlockId>,
+) {
+    let mut effects: Vec<LockEffect> = Vec::new();
+    let mut held: Vec<String> = Vec::new();
+
+    for stmt in stmts {
+        if let Some((then_block, else_block)) = if_branches(stmt) {
+            cfg.add_block(current_id, LockBlock { stmts: std::mem::take(&mut effects), successors: vec![] });
+
+            let then_id = fresh_id(next_id);
+            let after_id = fresh_id(next_id);
+            match else_block {
+                Some(else_block) => {
+                    let else_id = fresh_id(next_id);
+                    cfg.add_if_edges(current_id, None, then_id, Some(else_id));
+                    lower_lock_stmts(cfg, next_id, else_id, &else_block.stmts, Some(after_id));
+                }
+                None => {
+                    // No `else`: the implicit empty-else path goes straight
+                    // to `after_id`, same as `lower_stmts` models an absent
+                    // else for the unreachable-code CFG.
+                    cfg.add_if_edges(current_id, None, then_id, Some(after_id));
+                }
+            }
+            lower_lock_stmts(cfg, next_id, then_id, &then_block.stmts, Some(after_id));
+
+            current_id = after_id;
+            continue;
+        }
+
+        if let Some(binding) = lock_binding(stmt) {
+            effects.push(LockEffect::Lock(binding.clone()));
+            held.push(binding);
+        } else if let Some(binding) = drop_binding(stmt) {
+            effects.push(LockEffect::Drop(binding.clone()));
+            held.retain(|b| *b != binding);
+        } else if let Some((span, callee)) = call_stmt(stmt) {
+            effects.push(LockEffect::PanicCapableCall { span, callee });
+        }
+    }
+
+    // Every guard still held when this scope ends drops at its
+    // end-of-scope point, same as it would at runtime.
+    effects.extend(held.into_iter().map(LockEffect::Drop));
+    cfg.add_block(current_id, LockBlock { stmts: effects, successors: after.into_iter().collect() });
+}
+
+/// A top-level `if <cond> { .. } [else { .. }]`. An `else if` chain (an
+/// `Expr::If` rather than a block on the else side) isn't modeled — the
+/// whole `if` statement falls through as a single opaque, always-live
+/// statement instead, same as a `match` or a nested loop.
+fn if_branches(stmt: &syn::Stmt) -> Option<(&syn::Block, Option<&syn::Block>)> {
+    let syn::Stmt::Expr(syn::Expr::If(expr_if), _) = stmt else { return None };
+    let else_block = match &expr_if.else_branch {
+        None => None,
+        Some((_, else_expr)) => match &**else_expr {
+            syn::Expr::Block(block) => Some(&block.block),
+            _ => return None,
+        },
+    };
+    Some((&expr_if.then_branch, else_block))
+}
+
+/// A top-level `let binding = <expr>;` where `<expr>` calls `.lock()`,
+/// `.read()`, or `.write()` somewhere in it — the `Mutex`/`RwLock`
+/// guard-acquisition methods.
+fn lock_binding(stmt: &syn::Stmt) -> Option<String> {
+    let syn::Stmt::Local(local) = stmt else { return None };
+    let init = local.init.as_ref()?;
+    if !calls_lock_method(&init.expr) {
+        return None;
+    }
+    let syn::Pat::Ident(pat_ident) = &local.pat else { return None };
+    Some(pat_ident.ident.to_string())
+}
+
+fn calls_lock_method(expr: &syn::Expr) -> bool {
+    struct LockMethodFinder(bool);
+
+    impl<'ast> Visit<'ast> for LockMethodFinder {
+        fn visit_expr_method_call(&mut self, call: &'ast syn::ExprMethodCall) {
+            if matches!(call.method.to_string().as_str(), "lock" | "read" | "write") {
+                self.0 = true;
+            }
+            visit::visit_expr_method_call(self, call);
+        }
+    }
+
+    let mut finder = LockMethodFinder(false);
+    finder.visit_expr(expr);
+    finder.0
+}
+
+/// A top-level `drop(binding);` call, the explicit-early-drop shape.
+fn drop_binding(stmt: &syn::Stmt) -> Option<String> {
+    let syn::Stmt::Expr(syn::Expr::Call(call), _) = stmt else { return None };
+    let syn::Expr::Path(func_path) = &*call.func else { return None };
+    if path_string(&func_path.path) != "drop" {
+        return None;
+    }
+    let syn::Expr::Path(arg_path) = call.args.first()? else { return None };
+    Some(path_string(&arg_path.path))
+}
+
+/// A top-level `callee(..);` call statement, other than `drop(..)` — a
+/// candidate site for the lock-poisoning pass to cross-check against
+/// [`crate::panic_analysis`]'s interprocedural facts once those are
+/// available.
+fn call_stmt(stmt: &syn::Stmt) -> Option<(Span, FunctionId)> {
+    let syn::Stmt::Expr(syn::Expr::Call(call), _) = stmt else { return None };
+    let syn::Expr::Path(func_path) = &*call.func else { return None };
+    let name = path_string(&func_path.path);
+    if name == "drop" {
+        return None;
+    }
+    let span = to_span(func_path.path.segments.last()?.ident.span());
+    Some((span, FunctionId::new(name)))
+}
+
+/// Lowers a function body's top-level statement sequence into an
+/// [`ExceptionCfg`] for [`crate::exception_safety`]. This recognizes two
+/// syntactic hazard shapes: `mem::replace(&mut NAME, ..)` (and its
+/// `let`-bound form) opens a hazard named after the place being swapped
+/// out, and `ptr::write(PLACE, ..)` opens one named after the raw pointer
+/// being written through, since both put a location into a transiently
+/// broken state before the real value lands. A plain `NAME = ..;`
+/// assignment closes the hazard on that place, modeling the real value
+/// being written back. `unsafe { .. }` blocks are transparent — their
+/// statements are folded into the surrounding sequence rather than
+/// treated as their own scope, since a hazard opened inside one is
+/// routinely closed (or first observed by a panic) after it ends. Like
+/// `lower_lock_cfg` before branching support was added, this only
+/// recognizes the one straight-line sequence shape.
+fn lower_exception_cfg(block: &syn::Block) -> ExceptionCfg {
+    let stmts = flatten_unsafe_blocks(&block.stmts);
+    let mut effects = Vec::new();
+
+    for stmt in &stmts {
+        if let Some(location) = hazard_open_location(stmt) {
+            effects.push(ExceptionEffect::OpenHazard(location));
+        } else if let Some(location) = hazard_close_location(stmt) {
+            effects.push(ExceptionEffect::CloseHazard(location));
+        } else if let Some((span, _)) = call_stmt(stmt) {
+            effects.push(ExceptionEffect::PanicPoint(span));
+        }
+    }
+
+    let entry = BlockId(0);
+    let mut cfg = ExceptionCfg::new(entry);
+    cfg.add_block(entry, ExceptionBlock { stmts: effects, successors: vec![] });
+    cfg
+}
+
+/// Expands `unsafe { .. }` block statements inline, recursively, so
+/// hazard ops written inside one are seen at the same level as everything
+/// else — an `unsafe` block isn't a real control-flow boundary here.
+fn flatten_unsafe_blocks(stmts: &[syn::Stmt]) -> Vec<&syn::Stmt> {
+    let mut out = Vec::new();
+    for stmt in stmts {
+        if let syn::Stmt::Expr(syn::Expr::Unsafe(expr_unsafe), _) = stmt {
+            out.extend(flatten_unsafe_blocks(&expr_unsafe.block.stmts));
+        } else {
+            out.push(stmt);
+        }
+    }
+    out
+}
+
+/// The expression a statement evaluates, whether it's a bare expression
+/// statement or a `let`'s initializer — the two shapes real code uses for
+/// both hazard ops (`mem::replace`'s return value is sometimes kept,
+/// `ptr::write`'s never is).
+fn stmt_expr(stmt: &syn::Stmt) -> Option<&syn::Expr> {
+    match stmt {
+        syn::Stmt::Expr(expr, _) => Some(expr),
+        syn::Stmt::Local(local) => local.init.as_ref().map(|init| &*init.expr),
+        _ => None,
+    }
+}
+
+fn hazard_open_location(stmt: &syn::Stmt) -> Option<String> {
+    let syn::Expr::Call(call) = stmt_expr(stmt)? else { return None };
+    let syn::Expr::Path(func_path) = &*call.func else { return None };
+    match path_string(&func_path.path).as_str() {
+        "mem::replace" | "std::mem::replace" => {
+            let syn::Expr::Reference(reference) = call.args.first()? else { return None };
+            Some(expr_to_string(&reference.expr))
+        }
+        "ptr::write" | "std::ptr::write" => Some(expr_to_string(call.args.first()?)),
+        _ => None,
+    }
+}
+
+/// A top-level `NAME = ..;` plain assignment — the place being written
+/// back, restoring whatever hazard op temporarily broke it.
+fn hazard_close_location(stmt: &syn::Stmt) -> Option<String> {
+    let syn::Expr::Assign(assign) = stmt_expr(stmt)? else { return None };
+    let syn::Expr::Path(path) = &*assign.left else { return None };
+    Some(path_string(&path.path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rendered(src: &str) -> String {
+        expr_to_string(&syn::parse_str(src).expect("fixture expr should parse"))
+    }
+
+    #[test]
+    fn cast_keeps_the_space_that_separates_the_cast_keyword_from_the_target_type() {
+        assert_eq!(rendered("x as i64"), "x as i64");
+        assert_eq!(rendered("x as i32 - y"), "x as i32-y");
+    }
+
+    #[test]
+    fn punctuation_adjacent_to_a_word_is_still_compacted() {
+        assert_eq!(rendered("data[i]"), "data[i]");
+        assert_eq!(rendered("foo::bar(1, 2)"), "foo::bar(1,2)");
+        assert_eq!(rendered("a - b - c"), "a-b-c");
+    }
+}
@@ -0,0 +1,308 @@
+//! Unreachable-code-after-divergence pass.
+//!
+//! Generalizes the "unreachable code after panic" observation (the
+//! `println!` after `panic!` in `process_data`, or the statements after
+//! the `if true { panic!() }` in `main`) into a CFG-based dead-code pass:
+//! any statement in a block the entry can't reach is reported, with a
+//! note pointing at the diverging statement that dominates it. Diverging
+//! statements are `panic!`, `return`, `break`/`continue`,
+//! `std::process::exit`, and a `loop {}` with no `break`. Calls to
+//! functions with a `!` return type aren't recognized here: nothing in
+//! this crate's model tracks callee return types (see
+//! [`crate::model::CallGraph`]), so there's no signal to check against
+//! without a type-aware front end.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::cfg::Block;
+use crate::diagnostics::{Diagnostic, Severity};
+use crate::model::{FunctionId, Span};
+
+pub use crate::cfg::BlockId;
+
+/// Why a statement never hands control to whatever follows it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DivergingKind {
+    Panic,
+    Return,
+    BreakOrContinue,
+    ProcessExit,
+    InfiniteLoopWithNoBreak,
+}
+
+#[derive(Debug, Clone)]
+pub struct BlockStmt {
+    pub span: Span,
+    pub diverges: Option<DivergingKind>,
+}
+
+pub type BasicBlock = Block<BlockStmt>;
+pub type Cfg = crate::cfg::Cfg<BlockStmt>;
+
+/// Forward reachability from the entry block, following only the
+/// successor edges the CFG actually recorded. A block containing a
+/// diverging statement contributes no live successors past that point,
+/// regardless of what was recorded for it.
+fn reachable_blocks(cfg: &Cfg) -> HashSet<BlockId> {
+    let mut reachable = HashSet::new();
+    let mut worklist = VecDeque::from([cfg.entry]);
+
+    while let Some(id) = worklist.pop_front() {
+        if !reachable.insert(id) {
+            continue;
+        }
+        let Some(block) = cfg.blocks.get(&id) else { continue };
+        if block.stmts.iter().any(|s| s.diverges.is_some()) {
+            continue;
+        }
+        worklist.extend(block.successors.iter().copied());
+    }
+
+    reachable
+}
+
+/// Finds the diverging statement that dominates an unreachable block, by
+/// walking backward over predecessor edges (through other unreachable
+/// blocks if need be) until a *reachable* predecessor turns up. Such a
+/// predecessor must itself contain a diverging statement — otherwise
+/// `reachable_blocks` would have followed its edge into `start` and it
+/// wouldn't be unreachable at all — so its span is the cause.
+fn dominating_divergence(
+    cfg: &Cfg,
+    reachable: &HashSet<BlockId>,
+    preds: &HashMap<BlockId, Vec<BlockId>>,
+    start: BlockId,
+) -> Option<Span> {
+    let mut seen = HashSet::new();
+    let mut worklist = VecDeque::from([start]);
+
+    while let Some(id) = worklist.pop_front() {
+        if !seen.insert(id) {
+            continue;
+        }
+        for &pred in preds.get(&id).map(Vec::as_slice).unwrap_or(&[]) {
+            if reachable.contains(&pred) {
+                if let Some(span) = cfg.blocks.get(&pred).and_then(|block| {
+                    block.stmts.iter().find_map(|s| s.diverges.map(|_| s.span))
+                }) {
+                    return Some(span);
+                }
+            } else {
+                worklist.push_back(pred);
+            }
+        }
+    }
+
+    None
+}
+
+/// Reports every statement unreachable from the function entry, each
+/// pointing back at the diverging statement that dominates it — either
+/// one earlier in the same block, or (for a whole unreachable block) the
+/// diverging statement in whichever live predecessor made it so.
+///
+/// Diagnostics are returned sorted by source position: `cfg.blocks` is a
+/// `HashMap`, so iterating it directly would reorder diagnostics across
+/// runs on an identical crate.
+pub fn check(function: &FunctionId, cfg: &Cfg) -> Vec<Diagnostic> {
+    let reachable = reachable_blocks(cfg);
+    let preds = cfg.predecessors();
+    let mut diagnostics = Vec::new();
+
+    for (id, block) in &cfg.blocks {
+        if reachable.contains(id) {
+            let mut dominating: Option<Span> = None;
+            for stmt in &block.stmts {
+                if let Some(span) = dominating {
+                    diagnostics.push(Diagnostic::new(
+                        function.clone(),
+                        stmt.span,
+                        Severity::Warning,
+                        format!(
+                            "unreachable statement; control never passes the diverging statement at {}:{}",
+                            span.line, span.column
+                        ),
+                    ));
+                }
+                if stmt.diverges.is_some() && dominating.is_none() {
+                    dominating = Some(stmt.span);
+                }
+            }
+            continue;
+        }
+
+        let dominating = dominating_divergence(cfg, &reachable, &preds, *id);
+        for stmt in &block.stmts {
+            let message = match dominating {
+                Some(span) => format!(
+                    "unreachable block; control never passes the diverging statement at {}:{}",
+                    span.line, span.column
+                ),
+                None => "unreachable block; not reachable from the function entry".to_string(),
+            };
+            diagnostics.push(Diagnostic::new(function.clone(), stmt.span, Severity::Warning, message));
+        }
+    }
+
+    diagnostics.sort_by_key(|d| (d.span.line, d.span.column));
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Mirrors `helper::process_data`'s `panic!("Another intentional
+    /// panic!"); println!("This will never execute");` — a diverging
+    /// statement followed by a live one in the *same* block.
+    #[test]
+    fn flags_statement_after_panic_in_the_same_block() {
+        let entry = BlockId(0);
+        let mut cfg = Cfg::new(entry);
+        cfg.add_block(
+            entry,
+            BasicBlock {
+                stmts: vec![
+                    BlockStmt { span: Span { line: 11, column: 5 }, diverges: Some(DivergingKind::Panic) },
+                    BlockStmt { span: Span { line: 12, column: 5 }, diverges: None },
+                ],
+                successors: vec![],
+            },
+        );
+
+        let diagnostics = check(&FunctionId::new("helper::process_data"), &cfg);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].span, Span { line: 12, column: 5 });
+        assert!(diagnostics[0].message.contains("11:5"));
+    }
+
+    /// Mirrors `main`'s `if true { panic!(...) } helper::process_data(data);`:
+    /// a constant-`true` condition means only the then-branch edge is
+    /// live, so the block after the `if` is unreachable as a whole, and
+    /// the dominating diverging statement is found by walking back to the
+    /// (reachable) then-branch block.
+    #[test]
+    fn constant_true_condition_makes_the_rest_of_the_function_unreachable() {
+        let entry = BlockId(0);
+        let then_block = BlockId(1);
+        let after_if = BlockId(2);
+
+        let mut cfg = Cfg::new(entry);
+        cfg.add_block(entry, BasicBlock { stmts: vec![], successors: vec![] });
+        cfg.add_if_edges(entry, Some(true), then_block, None);
+        cfg.add_block(
+            then_block,
+            BasicBlock {
+                stmts: vec![BlockStmt { span: Span { line: 14, column: 9 }, diverges: Some(DivergingKind::Panic) }],
+                successors: vec![after_if],
+            },
+        );
+        cfg.add_block(
+            after_if,
+            BasicBlock {
+                stmts: vec![BlockStmt { span: Span { line: 17, column: 5 }, diverges: None }],
+                successors: vec![],
+            },
+        );
+
+        let diagnostics = check(&FunctionId::new("main"), &cfg);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].span, Span { line: 17, column: 5 });
+        assert!(diagnostics[0].message.contains("14:9"));
+    }
+
+    /// Mirrors `if false { panic!(...); } println!(...);`: the then-branch
+    /// is correctly reported dead (it's never taken), but the code after
+    /// the `if` must stay reachable via the implicit-else edge straight
+    /// from `entry` to `after_if` — regressed once by a lowering bug that
+    /// dropped that edge and flagged the live code after the `if` too.
+    #[test]
+    fn constant_false_condition_does_not_orphan_the_code_after_the_if() {
+        let entry = BlockId(0);
+        let then_block = BlockId(1);
+        let after_if = BlockId(2);
+
+        let mut cfg = Cfg::new(entry);
+        cfg.add_block(entry, BasicBlock { stmts: vec![], successors: vec![] });
+        cfg.add_if_edges(entry, Some(false), then_block, Some(after_if));
+        cfg.add_block(
+            then_block,
+            BasicBlock {
+                stmts: vec![BlockStmt { span: Span { line: 14, column: 9 }, diverges: Some(DivergingKind::Panic) }],
+                successors: vec![after_if],
+            },
+        );
+        cfg.add_block(
+            after_if,
+            BasicBlock {
+                stmts: vec![BlockStmt { span: Span { line: 17, column: 5 }, diverges: None }],
+                successors: vec![],
+            },
+        );
+
+        let diagnostics = check(&FunctionId::new("main"), &cfg);
+        assert!(
+            !diagnostics.iter().any(|d| d.span == Span { line: 17, column: 5 }),
+            "code after `if false {{ .. }}` should stay reachable, got: {diagnostics:#?}"
+        );
+    }
+
+    /// A `loop {}` with no `break` diverges just like `panic!` or `return`:
+    /// nothing after it in the same block is ever reached.
+    #[test]
+    fn infinite_loop_with_no_break_makes_the_rest_of_the_block_unreachable() {
+        let entry = BlockId(0);
+        let mut cfg = Cfg::new(entry);
+        cfg.add_block(
+            entry,
+            BasicBlock {
+                stmts: vec![
+                    BlockStmt {
+                        span: Span { line: 5, column: 5 },
+                        diverges: Some(DivergingKind::InfiniteLoopWithNoBreak),
+                    },
+                    BlockStmt { span: Span { line: 6, column: 5 }, diverges: None },
+                ],
+                successors: vec![],
+            },
+        );
+
+        let diagnostics = check(&FunctionId::new("spins"), &cfg);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].span, Span { line: 6, column: 5 });
+        assert!(diagnostics[0].message.contains("5:5"));
+    }
+
+    /// Two unreachable statements in blocks whose `HashMap` iteration order
+    /// isn't guaranteed: output must still come back sorted by source
+    /// position.
+    #[test]
+    fn diagnostics_are_sorted_by_span_regardless_of_block_iteration_order() {
+        let entry = BlockId(0);
+        let mut cfg = Cfg::new(entry);
+        cfg.add_block(
+            entry,
+            BasicBlock {
+                stmts: vec![
+                    BlockStmt { span: Span { line: 40, column: 1 }, diverges: Some(DivergingKind::Panic) },
+                    BlockStmt { span: Span { line: 41, column: 1 }, diverges: None },
+                ],
+                successors: vec![BlockId(1)],
+            },
+        );
+        cfg.add_block(
+            BlockId(1),
+            BasicBlock {
+                stmts: vec![
+                    BlockStmt { span: Span { line: 10, column: 1 }, diverges: Some(DivergingKind::Panic) },
+                    BlockStmt { span: Span { line: 11, column: 1 }, diverges: None },
+                ],
+                successors: vec![],
+            },
+        );
+
+        let diagnostics = check(&FunctionId::new("helper::process_data"), &cfg);
+        let spans: Vec<_> = diagnostics.iter().map(|d| (d.span.line, d.span.column)).collect();
+        assert_eq!(spans, vec![(10, 1), (11, 1), (41, 1)]);
+    }
+}
@@ -0,0 +1,255 @@
+//! Integration test: runs the full front-end-to-diagnostics pipeline against
+//! the real, on-disk `runtime-tests/rust-sample` fixture crate (not a
+//! hand-built `CallGraph`/`Cfg`), and asserts on the diagnostics the passes
+//! actually produce from parsing it.
+
+use std::path::PathBuf;
+
+use insight::model::FunctionId;
+use insight::panic_analysis::{self, PanicOrigin};
+use insight::panic_strategy::AnalysisContext;
+use insight::{checked_arithmetic, exception_safety, lock_poisoning, recoverable_refactor, source, unreachable_code};
+
+fn fixture_entry() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("runtime-tests/rust-sample/src/main.rs")
+}
+
+#[test]
+fn lowers_both_fixture_files_into_one_call_graph() {
+    let lowered = source::lower_crate(&fixture_entry()).expect("fixture should read and parse");
+
+    let main = FunctionId::new("main");
+    let process_data = FunctionId::new("helper::process_data");
+
+    assert!(lowered.graph.functions().any(|f| *f == main), "main should be lowered from main.rs");
+    assert!(
+        lowered.graph.functions().any(|f| *f == process_data),
+        "helper::process_data should be lowered from the sibling helper.rs via `mod helper;`"
+    );
+    assert!(
+        lowered.graph.calls_from(&main).iter().any(|call| call.callee == process_data),
+        "main's call to helper::process_data should be a recorded call-graph edge"
+    );
+}
+
+#[test]
+fn panic_analysis_propagates_through_the_real_call_graph() {
+    let lowered = source::lower_crate(&fixture_entry()).expect("fixture should read and parse");
+    let main = FunctionId::new("main");
+    let process_data = FunctionId::new("helper::process_data");
+
+    let facts = panic_analysis::propagate(&lowered.graph, &lowered.local_panics);
+
+    assert!(facts.may_panic(&main), "main panics locally via `.unwrap()` and `panic!`");
+    assert!(facts.may_panic(&process_data), "process_data panics locally via `panic!`");
+
+    let diagnostics = panic_analysis::diagnose(&lowered.graph, &facts);
+    assert!(
+        diagnostics.iter().any(|d| d.function == main && d.message.contains("unwrap")),
+        "expected an `.unwrap()` diagnostic on main, got: {diagnostics:#?}"
+    );
+    assert!(
+        diagnostics.iter().any(|d| d.function == process_data && d.message.contains("panic!")),
+        "expected an explicit-panic diagnostic on process_data, got: {diagnostics:#?}"
+    );
+}
+
+#[test]
+fn lock_poisoning_flags_a_mutex_guard_held_across_the_fixture_helpers_panicking_call() {
+    let lowered = source::lower_crate(&fixture_entry()).expect("fixture should read and parse");
+    let function = FunctionId::new("guard_held_across_a_panicking_call");
+    let process_data = FunctionId::new("helper::process_data");
+
+    let facts = panic_analysis::propagate(&lowered.graph, &lowered.local_panics);
+    assert!(facts.may_panic(&process_data), "process_data panics locally via `panic!`");
+
+    let cfg = &lowered.lock_cfgs[&function];
+    let diagnostics = lock_poisoning::check(&function, cfg, &facts, &AnalysisContext::default());
+    assert!(
+        !diagnostics.is_empty(),
+        "expected the `Mutex` guard held across the call into helper::process_data to be flagged, got: {diagnostics:#?}"
+    );
+}
+
+#[test]
+fn lock_poisoning_flags_a_guard_dropped_on_only_one_branch_as_held_at_the_merge() {
+    let lowered = source::lower_crate(&fixture_entry()).expect("fixture should read and parse");
+    let function = FunctionId::new("guard_locked_on_one_branch_is_held_at_the_merge");
+    let process_data = FunctionId::new("helper::process_data");
+
+    let facts = panic_analysis::propagate(&lowered.graph, &lowered.local_panics);
+    assert!(facts.may_panic(&process_data), "process_data panics locally via `panic!`");
+
+    let cfg = &lowered.lock_cfgs[&function];
+    let diagnostics = lock_poisoning::check(&function, cfg, &facts, &AnalysisContext::default());
+    assert!(
+        !diagnostics.is_empty(),
+        "expected the guard dropped on only the `if` branch to be flagged as held at the merge \
+         into helper::process_data, got: {diagnostics:#?}"
+    );
+}
+
+#[test]
+fn exception_safety_flags_a_mem_replace_hazard_left_open_across_a_panicking_call() {
+    let lowered = source::lower_crate(&fixture_entry()).expect("fixture should read and parse");
+    let function = FunctionId::new("hazard_left_open_across_a_panicking_call");
+    let process_data = FunctionId::new("helper::process_data");
+
+    let facts = panic_analysis::propagate(&lowered.graph, &lowered.local_panics);
+    assert!(facts.may_panic(&process_data), "process_data panics locally via `panic!`");
+
+    let cfg = &lowered.exception_cfgs[&function];
+    let diagnostics = exception_safety::check(&function, cfg, &facts, &AnalysisContext::default());
+    assert!(
+        !diagnostics.is_empty(),
+        "expected the `mem::replace`-opened hazard on `state` to be flagged as live across the \
+         panicking call into helper::process_data, got: {diagnostics:#?}"
+    );
+}
+
+#[test]
+fn recoverable_refactor_suggests_unwrap_for_the_infallible_real_caller_of_a_recoverable_site() {
+    let lowered = source::lower_crate(&fixture_entry()).expect("fixture should read and parse");
+    let divide_by_minus_one = FunctionId::new("helper::divide_by_minus_one");
+    let main = FunctionId::new("main");
+
+    let site = lowered
+        .recoverable_sites
+        .iter()
+        .find(|site| site.function == divide_by_minus_one)
+        .expect("`x / -1` in divide_by_minus_one should be lowered into a recoverable site");
+    assert!(
+        site.call_sites.iter().any(|call| call.caller == main && !call.caller_returns_result),
+        "expected main (which doesn't return Result) among divide_by_minus_one's real callers, got: {:#?}",
+        site.call_sites
+    );
+
+    let diagnostics = recoverable_refactor::suggest(std::slice::from_ref(site));
+    assert!(
+        diagnostics
+            .iter()
+            .any(|d| d.function == main && d.suggestions.iter().any(|s| s.replacement.ends_with(".unwrap()"))),
+        "expected an `.unwrap()` call-site suggestion for main, got: {diagnostics:#?}"
+    );
+}
+
+#[test]
+fn checked_arithmetic_flags_the_fixtures_overflow_prone_multiplication() {
+    let lowered = source::lower_crate(&fixture_entry()).expect("fixture should read and parse");
+
+    let diagnostics = checked_arithmetic::lint(&lowered.arith_exprs);
+    assert!(
+        diagnostics.iter().any(|d| d.function == FunctionId::new("helper::process_data")),
+        "expected `data[i] * 1000000` in process_data to be flagged, got: {diagnostics:#?}"
+    );
+}
+
+#[test]
+fn checked_arithmetic_flags_a_non_commutative_op_with_its_constant_on_the_left() {
+    let lowered = source::lower_crate(&fixture_entry()).expect("fixture should read and parse");
+
+    let diagnostics = checked_arithmetic::lint(&lowered.arith_exprs);
+    assert!(
+        diagnostics.iter().any(|d| d.function == FunctionId::new("helper::shift_by_runtime_amount")),
+        "expected `1u32 << n` to be flagged even though its constant is the left operand, got: {diagnostics:#?}"
+    );
+    assert!(
+        diagnostics.iter().any(|d| d.function == FunctionId::new("helper::negate_via_subtraction")),
+        "expected `0 - x` to be flagged even though its constant is the left operand, got: {diagnostics:#?}"
+    );
+}
+
+#[test]
+fn checked_arithmetic_flags_a_shift_against_its_operands_real_declared_width() {
+    let lowered = source::lower_crate(&fixture_entry()).expect("fixture should read and parse");
+    let shift_u8 = FunctionId::new("helper::shift_a_u8_by_a_too_wide_amount");
+
+    let expr = lowered
+        .arith_exprs
+        .iter()
+        .find(|e| e.function == shift_u8)
+        .expect("x << 10 in shift_a_u8_by_a_too_wide_amount should be lowered");
+    assert_eq!(expr.bit_width, 8, "x: u8's declared width should be read from the parameter type, not guessed");
+
+    let diagnostics = checked_arithmetic::lint(&lowered.arith_exprs);
+    assert!(
+        diagnostics.iter().any(|d| d.function == shift_u8),
+        "expected `x << 10` to be flagged against u8's real 8-bit width, got: {diagnostics:#?}"
+    );
+}
+
+#[test]
+fn unreachable_code_flags_both_fixture_shapes() {
+    let lowered = source::lower_crate(&fixture_entry()).expect("fixture should read and parse");
+    let main = FunctionId::new("main");
+    let process_data = FunctionId::new("helper::process_data");
+
+    let process_data_cfg = &lowered.unreachable_cfgs[&process_data];
+    let process_data_diagnostics = unreachable_code::check(&process_data, process_data_cfg);
+    assert!(
+        !process_data_diagnostics.is_empty(),
+        "expected the `println!` after `panic!` in process_data to be flagged"
+    );
+
+    let main_cfg = &lowered.unreachable_cfgs[&main];
+    let main_diagnostics = unreachable_code::check(&main, main_cfg);
+    assert!(
+        !main_diagnostics.is_empty(),
+        "expected the call to helper::process_data after `if true {{ panic!() }}` in main to be flagged"
+    );
+}
+
+#[test]
+fn division_by_the_literal_minus_one_is_flagged_as_integer_division() {
+    let lowered = source::lower_crate(&fixture_entry()).expect("fixture should read and parse");
+    let divide_by_minus_one = FunctionId::new("helper::divide_by_minus_one");
+
+    let facts = &lowered.local_panics[&divide_by_minus_one];
+    assert!(
+        facts.origins.iter().any(|(_, origin)| *origin == PanicOrigin::IntegerDivision),
+        "expected `x / -1` in divide_by_minus_one to be flagged as integer division, got: {facts:#?}"
+    );
+}
+
+#[test]
+fn unreachable_code_does_not_flag_live_code_after_a_constant_false_guard() {
+    let lowered = source::lower_crate(&fixture_entry()).expect("fixture should read and parse");
+    let guarded = FunctionId::new("unreachable_guard_does_not_hide_live_code");
+
+    let cfg = &lowered.unreachable_cfgs[&guarded];
+    let diagnostics = unreachable_code::check(&guarded, cfg);
+    // Only the dead `panic!()` inside `if false { .. }` should be
+    // flagged; the trailing `42` (the function's real, reachable tail
+    // expression) must not be.
+    assert_eq!(
+        diagnostics.len(),
+        1,
+        "expected only the dead `panic!()` inside `if false {{ .. }}` to be flagged, got: {diagnostics:#?}"
+    );
+}
+
+#[test]
+fn unreachable_code_flags_the_println_after_a_bare_exit_call() {
+    let lowered = source::lower_crate(&fixture_entry()).expect("fixture should read and parse");
+    let bail_out = FunctionId::new("helper::bail_out");
+
+    let cfg = &lowered.unreachable_cfgs[&bail_out];
+    let diagnostics = unreachable_code::check(&bail_out, cfg);
+    assert!(
+        !diagnostics.is_empty(),
+        "expected the `println!` after a bare `exit(1)` (imported via `use`) in bail_out to be flagged"
+    );
+}
+
+#[test]
+fn unreachable_code_flags_the_println_after_an_infinite_loop_with_no_break() {
+    let lowered = source::lower_crate(&fixture_entry()).expect("fixture should read and parse");
+    let spin_forever = FunctionId::new("helper::spin_forever");
+
+    let cfg = &lowered.unreachable_cfgs[&spin_forever];
+    let diagnostics = unreachable_code::check(&spin_forever, cfg);
+    assert!(
+        !diagnostics.is_empty(),
+        "expected the `println!` after `loop {{ .. }}` with no `break` in spin_forever to be flagged"
+    );
+}